@@ -0,0 +1,60 @@
+//! Compares `intersect_sorted_simd` against the scalar merge on ladders of a few thousand values.
+//!
+//! Run with `cargo bench --features simd`.
+
+use attempt::intersect_sorted_simd;
+use std::{ hint::black_box, time::{ Duration, Instant } };
+
+const ROUNDS : u32 = 2000;
+
+fn intersect_scalar( original : &[ i32 ], allowed : &[ i32 ] ) -> Vec< i32 >
+{
+  let mut vec = vec![];
+  let ( mut i, mut j ) = ( 0, 0 );
+  while i < original.len() && j < allowed.len()
+  {
+    match original[ i ].cmp( &allowed[ j ] )
+    {
+      std::cmp::Ordering::Less => i += 1,
+      std::cmp::Ordering::Greater => j += 1,
+      std::cmp::Ordering::Equal =>
+      {
+        if vec.last() != Some( &original[ i ] )
+        {
+          vec.push( original[ i ] );
+        }
+        i += 1;
+      }
+    }
+  }
+  vec
+}
+
+fn measure( f : impl Fn() -> Vec< i32 > ) -> Duration
+{
+  let start = Instant::now();
+  for _ in 0..ROUNDS
+  {
+    black_box( f() );
+  }
+  start.elapsed() / ROUNDS
+}
+
+fn main()
+{
+  for ( size, every ) in [ ( 2000, 3 ), ( 4000, 7 ), ( 8000, 13 ) ]
+  {
+    let original : Vec< i32 > = ( 0..size ).map( | num | num * 120 ).collect();
+    let allowed : Vec< i32 > = ( 0..size * 4 ).filter( | num | num % every != 0 ).map( | num | num * 30 ).collect();
+    assert_eq!( intersect_sorted_simd( &original, &allowed ), intersect_scalar( &original, &allowed ) );
+
+    let scalar = measure( || intersect_scalar( black_box( &original ), black_box( &allowed ) ) );
+    let simd = measure( || intersect_sorted_simd( black_box( &original ), black_box( &allowed ) ) );
+    println!
+    (
+      "{size} available, {} allowed: scalar {scalar:?}, simd {simd:?}, speedup {:.2}x",
+      allowed.len(),
+      scalar.as_secs_f64() / simd.as_secs_f64()
+    );
+  }
+}
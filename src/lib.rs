@@ -2,80 +2,772 @@
 //!
 //! Provides the `attempt` function which returns a vector of
 //! values filtered by parameters
+//!
+//! # Sorted input
+//!
+//! The algorithm relies on binary search, so it assumes that `available` values are sorted
+//! in ascending order. `attempt` checks it and sorts a copy if needed, `attempt_checked`
+//! reports unsorted input as an error and `attempt_sorted_unchecked` trusts the caller.
+//!
+//! The reducers, `reduce_by_allowed` and `reduce_by_preferred`, apply a single stage of
+//! filtering and always trust the caller as well.
+//!
+//! # Features
+//!
+//! - `std`, enabled by default, implements `std::error::Error` for `AttemptError` and
+//!   `ContractViolation`, and provides `selection_entropy`. Without it the crate is `no_std`
+//!   and only requires `alloc`.
+//! - `serde` implements serialization of `ValueT` and `AuditLog`.
+//! - `csv` provides `to_csv_row` for offline analysis of selections.
+//! - `futures` provides `select_stream` for preferences arriving as an asynchronous stream.
+//! - `simd` switches `intersect_sorted_simd`, which `reduce_by_allowed` uses for many allowed
+//!   numbers, to a branch-free chunked merge that the compiler may auto-vectorize.
+
+#![ cfg_attr( not( feature = "std" ), no_std ) ]
+
+extern crate alloc;
+
+use alloc::{ borrow::Cow, collections::{ BTreeMap, BTreeSet }, vec, vec::Vec };
+use core::ops::RangeInclusive;
 
 /// Describes value used by `allowed` and `preferred` vectors.
-#[ derive( PartialEq, Eq ) ]
-pub enum Value 
+///
+/// Generic over the type of values, which only has to be ordered.
+///
+/// With `serde` feature enabled, values can be serialized and deserialized. `Value::Any` is
+/// represented as string `"any"`, `Value::Number` as the bare number, `Value::Range` as
+/// array of its start and end, `Value::AtLeast`, `Value::AtMost` and `Value::Index` as objects
+/// with single `at_least`, `at_most` or `index` field.
+#[ derive( Debug, Clone, Copy, PartialEq, Eq ) ]
+pub enum ValueT< T >
 {
   /// For `allowed` cancels filtering of `avaliable` vector.
   ///
   /// For `preferred` cancels disables reducing by number of preferences.
   Any,
   /// Stores the regular value, unused if vector contains `Value::Any` alongside.
-  Number( i32 ),
+  Number( T ),
+  /// Stores the inclusive range of values, unused if vector contains `Value::Any` alongside.
+  ///
+  /// For `allowed` allows every value inside of the range.
+  ///
+  /// For `preferred` prefers every value inside of the range, or the closest one to the range.
+  ///
+  /// Inverted range, with start greater than end, is treated as if its bounds were swapped.
+  Range( T, T ),
+  /// Stores the lower threshold, unused if vector contains `Value::Any` alongside.
+  ///
+  /// For `allowed` allows every value not less than the threshold.
+  ///
+  /// For `preferred` prefers the smallest value not less than the threshold, or the largest
+  /// value if there are none.
+  AtLeast( T ),
+  /// Stores the upper threshold, unused if vector contains `Value::Any` alongside.
+  ///
+  /// For `allowed` allows every value not greater than the threshold.
+  ///
+  /// For `preferred` prefers the largest value not greater than the threshold, or the smallest
+  /// value if there are none.
+  AtMost( T ),
+  /// Stores the position of a value, unused if vector contains `Value::Any` alongside.
+  ///
+  /// For `allowed` allows the value at the position of sorted `available` vector.
+  ///
+  /// For `preferred` prefers the value at the position among allowed values, or the largest
+  /// value if the position is out of bounds.
+  Index( usize ),
+}
+
+/// Value of `allowed` and `preferred` vectors for `i32` values.
+pub type Value = ValueT< i32 >;
+
+/// Serializes values in the format described by `ValueT`, so `[ 360, [ 480, 720 ], "any" ]`
+/// is a valid vector of values.
+#[ cfg( feature = "serde" ) ]
+impl< T : serde::Serialize > serde::Serialize for ValueT< T >
+{
+  fn serialize< S : serde::Serializer >( &self, serializer : S ) -> Result< S::Ok, S::Error >
+  {
+    match self
+    {
+      ValueT::Any => serializer.serialize_str( "any" ),
+      ValueT::Number( num ) => num.serialize( serializer ),
+      ValueT::Range( start, end ) => ( start, end ).serialize( serializer ),
+      ValueT::AtLeast( num ) => ValueRepr::AtLeast { at_least : num }.serialize( serializer ),
+      ValueT::AtMost( num ) => ValueRepr::AtMost { at_most : num }.serialize( serializer ),
+      ValueT::Index( index ) => ValueRepr::< &T >::Index { index : *index }.serialize( serializer ),
+    }
+  }
+}
+
+/// Serialized representation of `ValueT`.
+#[ cfg( feature = "serde" ) ]
+#[ derive( serde::Serialize, serde::Deserialize ) ]
+#[ serde( untagged ) ]
+enum ValueRepr< T >
+{
+  Keyword( alloc::string::String ),
+  Number( T ),
+  Range( T, T ),
+  AtLeast { at_least : T },
+  AtMost { at_most : T },
+  Index { index : usize },
+}
+
+/// Deserializes values in the format produced by serialization.
+#[ cfg( feature = "serde" ) ]
+impl< 'de, T : serde::Deserialize< 'de > > serde::Deserialize< 'de > for ValueT< T >
+{
+  fn deserialize< D : serde::Deserializer< 'de > >( deserializer : D ) -> Result< Self, D::Error >
+  {
+    match ValueRepr::deserialize( deserializer )?
+    {
+      ValueRepr::Keyword( keyword ) if keyword == "any" => Ok( ValueT::Any ),
+      ValueRepr::Keyword( keyword ) =>
+      {
+        Err( serde::de::Error::custom( alloc::format!( "unknown value keyword `{keyword}`, expected `any`" ) ) )
+      }
+      ValueRepr::Number( num ) => Ok( ValueT::Number( num ) ),
+      ValueRepr::Range( start, end ) => Ok( ValueT::Range( start, end ) ),
+      ValueRepr::AtLeast { at_least } => Ok( ValueT::AtLeast( at_least ) ),
+      ValueRepr::AtMost { at_most } => Ok( ValueT::AtMost( at_most ) ),
+      ValueRepr::Index { index } => Ok( ValueT::Index( index ) ),
+    }
+  }
+}
+
+/// Describes values which can measure how far they are from each other.
+pub trait Distance : Ord + Copy
+{
+  /// Returns absolute difference between `self` and `other`.
+  fn distance( self, other : Self ) -> u64;
+}
+
+macro_rules! impl_distance
+{
+  ( $( $ty : ty ),* ) =>
+  {
+    $(
+      impl Distance for $ty
+      {
+        fn distance( self, other : Self ) -> u64
+        {
+          self.abs_diff( other ) as u64
+        }
+      }
+    )*
+  };
+}
+
+impl_distance!( i8, i16, i32, i64, isize, u8, u16, u32, u64, usize );
+
+/// Values with a representation that does not depend on the platform, so that
+/// `reduce_by_preferred_seeded` hashes them equally everywhere.
+pub trait StableKey : Copy
+{
+  /// Returns `self` widened to `i128`.
+  fn stable_key( self ) -> i128;
+}
+
+macro_rules! impl_stable_key
+{
+  ( $( $ty : ty ),* ) =>
+  {
+    $(
+      impl StableKey for $ty
+      {
+        fn stable_key( self ) -> i128
+        {
+          self as i128
+        }
+      }
+    )*
+  };
+}
+
+impl_stable_key!( i8, i16, i32, i64, isize, u8, u16, u32, u64, usize );
+
+/// Describes preferred value along with its importance for `reduce_by_weighted_preferred`.
+#[ derive( Debug, Clone, Copy, PartialEq ) ]
+pub struct WeightedValue< T >
+{
+  /// Preferred value.
+  pub value : ValueT< T >,
+  /// Importance of the preferred value, greater weight is more important.
+  pub weight : f32,
+}
+
+/// Decides which value to prefer when the preferred value lies between two available values.
+#[ derive( Debug, Default, Clone, Copy, PartialEq, Eq ) ]
+pub enum TieBreak
+{
+  /// Picks the closest value below of the preferred value.
+  PreferLower,
+  /// Picks the closest value above of the preferred value.
+  #[ default ]
+  PreferHigher,
+  /// Picks the value with the smallest distance to the preferred value, lower one on equality.
+  PreferNearest,
+}
+
+/// Describes order of values returned by `attempt_with_order`.
+#[ derive( Debug, Default, Clone, Copy, PartialEq, Eq ) ]
+pub enum OutputOrder
+{
+  /// Keeps values in order of preferences they were selected by.
+  #[ default ]
+  AsPreferred,
+  /// Sorts values in ascending order.
+  Ascending,
+  /// Sorts values in descending order.
+  Descending,
+}
+
+/// Describes why `attempt_checked` or `attempt_with_overflow` could not produce any values.
+#[ derive( Debug, Clone, Copy, PartialEq, Eq ) ]
+pub enum AttemptError
+{
+  /// The `available` vector is not sorted in ascending order.
+  UnsortedInput,
+  /// The `allowed` vector is empty, so no value can ever be allowed.
+  EmptyAllowed,
+  /// Inputs are valid, but none of the values satisfy the filters.
+  NoMatch,
+  /// One of the preferred values is outside of the ladder of allowed values.
+  OutOfRange,
+}
+
+impl core::fmt::Display for AttemptError
+{
+  fn fmt( &self, f : &mut core::fmt::Formatter< '_ > ) -> core::fmt::Result
+  {
+    match self
+    {
+      AttemptError::UnsortedInput => write!( f, "input values are not sorted in ascending order" ),
+      AttemptError::EmptyAllowed => write!( f, "allowed values are empty" ),
+      AttemptError::NoMatch => write!( f, "no value satisfies the filters" ),
+      AttemptError::OutOfRange => write!( f, "preferred value is outside of the allowed values" ),
+    }
+  }
+}
+
+#[ cfg( feature = "std" ) ]
+impl std::error::Error for AttemptError {}
+
+/// Describes selected values that `attempt_contract` found outside of the contract.
+#[ derive( Debug, Clone, PartialEq, Eq ) ]
+pub struct ContractViolation< T >
+{
+  /// Selected values missing from the contract, in order of the selection.
+  pub forbidden : Vec< T >,
+}
+
+impl< T : core::fmt::Debug > core::fmt::Display for ContractViolation< T >
+{
+  fn fmt( &self, f : &mut core::fmt::Formatter< '_ > ) -> core::fmt::Result
+  {
+    write!( f, "selected values {:?} are outside of the contract", self.forbidden )
+  }
+}
+
+#[ cfg( feature = "std" ) ]
+impl< T : core::fmt::Debug > std::error::Error for ContractViolation< T > {}
+
+/// Describes how a selection changed, as returned by `diff_selections`.
+#[ derive( Debug, Default, Clone, PartialEq, Eq ) ]
+pub struct SelectionDiff< T >
+{
+  /// Values of the new selection missing from the old one, in order of the new selection.
+  pub added : Vec< T >,
+  /// Values of the old selection missing from the new one, in order of the old selection.
+  pub removed : Vec< T >,
+  /// Values present in both selections, in order of the new selection.
+  pub kept : Vec< T >,
+}
+
+/// Describes preferred value along with an explicit fallback for `attempt_with_fallbacks`.
+#[ derive( Debug, Clone, Copy, PartialEq, Eq ) ]
+pub struct PreferenceWithFallback< T >
+{
+  /// Value to select if it is available and allowed.
+  pub primary : T,
+  /// Value to select if the primary one is not available or not allowed.
+  pub fallback : T,
+}
+
+/// Describes how `attempt_dup_policy` treats duplicates of `available` vector.
+#[ derive( Debug, Default, Clone, Copy, PartialEq, Eq ) ]
+pub enum DuplicatePolicy
+{
+  /// Every selected value is returned once.
+  #[ default ]
+  Collapse,
+  /// Every selected value is returned as many times as it occurs in `available`.
+  Preserve,
+}
+
+/// Describes allowed value that expires, for `attempt_timed`.
+#[ derive( Debug, Clone, Copy, PartialEq, Eq ) ]
+pub struct TimedAllowed< T >
+{
+  /// Allowed value.
+  pub value : ValueT< T >,
+  /// Moment the value stops being allowed at, in units chosen by the caller.
+  pub expires_at : u64,
+}
+
+/// Describes how `attempt_with_overflow` treats preferences outside of the ladder.
+#[ derive( Debug, Default, Clone, Copy, PartialEq, Eq ) ]
+pub enum OverflowPolicy
+{
+  /// Preferences below the smallest value or above the largest one select the closest bound.
+  #[ default ]
+  ClampToBound,
+  /// Preferences outside of the ladder select nothing.
+  Drop,
+  /// Preferences outside of the ladder make the selection fail.
+  Error,
+}
+
+/// Closest values below and above a preference, as returned by `attempt_exact_or_gap` for
+/// preferences it could not match and by `attempt_brackets`.
+pub type Gap< T > = ( Option< T >, Option< T > );
+
+/// Describes how the filters treat a single available value, as returned by `annotate`.
+#[ derive( Debug, Clone, PartialEq, Eq ) ]
+pub struct RungInfo< T >
+{
+  /// Available value.
+  pub value : T,
+  /// Whether `allowed` vector allows the value.
+  pub allowed : bool,
+  /// Positions of `preferred` values that select the value, in ascending order.
+  pub preferred_by : Vec< usize >,
+  /// Whether `attempt` selects the value.
+  pub selected : bool,
+}
+
+/// Describes values from `min` to `max` inclusive, `step` apart, for `attempt_spec`.
+#[ derive( Debug, Clone, Copy, PartialEq, Eq ) ]
+pub struct AllowedSpec< T >
+{
+  /// Smallest allowed value.
+  pub min : T,
+  /// Largest allowed value.
+  pub max : T,
+  /// Distance between adjacent allowed values, zero allows `min` only.
+  pub step : u64,
+}
+
+impl< T : Distance > AllowedSpec< T >
+{
+  /// Checks whether the specification allows the value.
+  pub fn allows( &self, num : T ) -> bool
+  {
+    self.min <= num && num <= self.max && num.distance( self.min ).is_multiple_of( self.step )
+  }
+}
+
+/// Describes how a value returned by `attempt_classified_each` relates to its preference.
+#[ derive( Debug, Clone, Copy, PartialEq, Eq ) ]
+#[ cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) ) ]
+#[ cfg_attr( feature = "serde", serde( rename_all = "snake_case" ) ) ]
+pub enum MatchKind
+{
+  /// The value satisfies the preference, like an exact match or a value inside of a range.
+  Exact,
+  /// The value is the closest one above the preference, which has values below it too.
+  RoundedUp,
+  /// The value is the closest one below the preference, which has values above it too.
+  RoundedDown,
+  /// The preference is above every value or its index is out of bounds, so the largest value
+  /// is taken.
+  ClampedHigh,
+  /// The preference is below every value, so the smallest value is taken.
+  ClampedLow,
+}
+
+/// Describes why `attempt_report` could not satisfy a preference.
+#[ derive( Debug, Clone, Copy, PartialEq, Eq ) ]
+pub enum UnmetReason
+{
+  /// The preference is below every allowed value.
+  BelowLadder,
+  /// The preference is above every allowed value.
+  AboveLadder,
+  /// The preference is available but not allowed, or no available value is allowed.
+  NotAllowed,
+}
+
+/// Describes what `attempt_report` made of a single preference.
+#[ derive( Debug, Clone, Copy, PartialEq, Eq ) ]
+pub enum PrefOutcome< T >
+{
+  /// The preference selects an allowed value.
+  Matched
+  {
+    /// Preferred number.
+    pref : T,
+    /// Selected value.
+    chosen : T,
+    /// Whether the selected value equals the preferred number.
+    exact : bool,
+  },
+  /// The preference could not be satisfied.
+  Unmet
+  {
+    /// Preferred number.
+    pref : T,
+    /// Why the preference could not be satisfied.
+    reason : UnmetReason,
+  },
+}
+
+/// Describes a single decision recorded by `attempt_audited`.
+#[ derive( Debug, Clone, Copy, PartialEq, Eq ) ]
+#[ cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) ) ]
+#[ cfg_attr( feature = "serde", serde( rename_all = "snake_case" ) ) ]
+pub enum AuditEvent< T >
+{
+  /// Distinct available value was checked against `allowed` vector.
+  Allowed
+  {
+    /// Available value.
+    value : T,
+    /// Whether the value is allowed.
+    allowed : bool,
+  },
+  /// Preference selected a value.
+  Selected
+  {
+    /// Position of the preference in `preferred` vector.
+    preference : usize,
+    /// Selected value.
+    value : T,
+    /// How the value relates to the preference, including fallbacks and clamps.
+    kind : MatchKind,
+  },
+  /// Preference resolved to a value an earlier preference selected, so it was collapsed.
+  Collapsed
+  {
+    /// Position of the preference in `preferred` vector.
+    preference : usize,
+    /// Value selected before.
+    value : T,
+  },
+}
+
+/// Records decisions of `attempt_audited` in the order they are made.
+///
+/// With `serde` feature enabled, the log can be serialized and deserialized.
+#[ derive( Debug, Default, Clone, PartialEq, Eq ) ]
+#[ cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) ) ]
+pub struct AuditLog< T >
+{
+  events : Vec< AuditEvent< T > >,
+}
+
+impl< T > AuditLog< T >
+{
+  /// Creates empty log.
+  pub fn new() -> Self
+  {
+    Self { events : vec![] }
+  }
+
+  /// Returns recorded events, oldest first.
+  pub fn events( &self ) -> &[ AuditEvent< T > ]
+  {
+    &self.events
+  }
+}
+
+/// Describes a single change of a selection, as returned by `selection_patch`.
+#[ derive( Debug, Clone, Copy, PartialEq, Eq ) ]
+pub enum Patch< T >
+{
+  /// The value was added to the selection.
+  Add( T ),
+  /// The value was removed from the selection.
+  Remove( T ),
+}
+
+/// Describes the selection made by `attempt_outcome`.
+#[ derive( Debug, Clone, PartialEq, Eq ) ]
+pub enum Outcome< T >
+{
+  /// No value is selected.
+  Empty
+  {
+    /// Whether some available values are allowed, so nothing matched the preferences, rather
+    /// than there being nothing to select from.
+    had_candidates : bool,
+  },
+  /// Selected values, never empty.
+  Selected( Vec< T > ),
+}
+
+/// Returns bounds of the range with start not greater than end.
+fn normalize_range< T : Ord + Copy >( start : T, end : T ) -> ( T, T )
+{
+  if start > end
+  {
+    ( end, start )
+  }
+  else
+  {
+    ( start, end )
+  }
+}
+
+impl< T : Ord + Copy > ValueT< T >
+{
+  /// Checks whether the value allows the value at `index` of sorted `available` vector when
+  /// used in `allowed` vector.
+  fn allows( &self, available : &[ T ], index : usize ) -> bool
+  {
+    let num = available[ index ];
+    match self
+    {
+      ValueT::Any => true,
+      ValueT::Number( other ) => *other == num,
+      ValueT::Range( start, end ) =>
+      {
+        let ( start, end ) = normalize_range( *start, *end );
+        start <= num && num <= end
+      }
+      ValueT::AtLeast( threshold ) => *threshold <= num,
+      ValueT::AtMost( threshold ) => num <= *threshold,
+      ValueT::Index( position ) => available.get( *position ) == Some( &num ),
+    }
+  }
+
+  /// Returns the value a preference is compared with when none of the values satisfy it, or
+  /// `None` if the preference is not compared with values.
+  fn key( &self ) -> Option< T >
+  {
+    match self
+    {
+      ValueT::Number( key ) | ValueT::AtLeast( key ) | ValueT::AtMost( key ) => Some( *key ),
+      ValueT::Range( start, end ) => Some( normalize_range( *start, *end ).0 ),
+      ValueT::Any | ValueT::Index( _ ) => None,
+    }
+  }
+
+  /// Describes how the value at `index` of sorted `ladder` selected by the preference relates
+  /// to it.
+  fn classify( &self, ladder : &[ T ], index : usize ) -> MatchKind
+  {
+    match ( self, self.key() )
+    {
+      ( ValueT::Index( position ), _ ) if *position >= ladder.len() => MatchKind::ClampedHigh,
+      _ if self.allows( ladder, index ) => MatchKind::Exact,
+      ( _, Some( key ) ) =>
+      {
+        let gap = ladder.partition_point( | other | *other < key );
+        match ( index >= gap, gap )
+        {
+          ( true, 0 ) => MatchKind::ClampedLow,
+          ( true, _ ) => MatchKind::RoundedUp,
+          ( false, gap ) if gap == ladder.len() => MatchKind::ClampedHigh,
+          ( false, _ ) => MatchKind::RoundedDown,
+        }
+      }
+      ( _, None ) => MatchKind::Exact,
+    }
+  }
+}
+
+/// Returns `available` as is if it is sorted in ascending order, or its sorted copy otherwise.
+fn sorted< T : Ord + Copy >( available : &[ T ] ) -> Cow< '_, [ T ] >
+{
+  if available.is_sorted()
+  {
+    return Cow::Borrowed( available );
+  }
+  let mut sorted = available.to_vec();
+  sorted.sort();
+  Cow::Owned( sorted )
+}
+
+/// Returns index of the `ladder` value that preferred `num` resolves to, following the rules of
+/// `reduce_by_preferred`, or `None` if `ladder` is empty.
+///
+/// Assumes `ladder` is sorted in ascending order.
+fn resolve_number< T : Ord + Copy >( ladder : &[ T ], num : T ) -> Option< usize >
+{
+  if ladder.is_empty()
+  {
+    return None;
+  }
+  Some( ladder.partition_point( | other | *other < num ).min( ladder.len() - 1 ) )
 }
 
 /// Reduces amount of available values by `allowed` filter vector.
 ///
-/// Returns vector with values both `original` and `allowed` have.
-fn reduce_by_allowed( original : &Vec< i32 >, allowed : &Vec< Value > ) -> Vec< i32 > 
+/// Returns vector with values both `original` and `allowed` have, or that `original` has
+/// inside of `allowed` ranges and thresholds or at `allowed` positions. Returns copy of
+/// `original` if `allowed` contains `Value::Any`.
+///
+/// Many allowed numbers are matched by merging them with `original` in `intersect_sorted_simd`
+/// rather than searching for every one of them.
+///
+/// Assumes `original` is sorted in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// assert_eq!
+/// (
+///   reduce_by_allowed( &[ 240, 360, 720 ], &[ Value::Number( 360 ), Value::Number( 1080 ) ] ),
+///   vec![ 360 ]
+/// );
+/// ```
+pub fn reduce_by_allowed< T : Ord + Copy >( original : &[ T ], allowed : &[ ValueT< T > ] ) -> Vec< T > 
 {
-  let mut vec : Vec< i32 > = vec![];
+  debug_assert!( original.is_sorted(), "`original` must be sorted in ascending order" );
+  if allowed.contains( &ValueT::Any )
+  {
+    return original.to_vec();
+  }
+  // a merge pays off once searching for every allowed number costs more than a pass over `original`
+  if allowed.len() * 16 >= original.len() && allowed.iter().all( | value | matches!( value, ValueT::Number( _ ) ) )
+  {
+    let mut numbers : Vec< T > = allowed.iter()
+    .filter_map( | value | match value
+    {
+      ValueT::Number( num ) => Some( *num ),
+      _ => None,
+    })
+    .collect();
+    numbers.sort();
+    return intersect_sorted_simd( original, &numbers );
+  }
+  let mut vec : Vec< T > = vec![];
   for value in allowed 
   {
-    if let Value::Number( num ) = value
+    match value
     {
-      if let Ok( _ ) = original.binary_search( num ) 
+      ValueT::Number( num ) =>
+      {
+        if original.binary_search( num ).is_ok()
+        {
+          vec.push( *num )
+        }
+      }
+      ValueT::Range( start, end ) =>
+      {
+        let ( start, end ) = normalize_range( *start, *end );
+        let lower = original.partition_point( | num | *num < start );
+        let upper = original.partition_point( | num | *num <= end );
+        vec.extend_from_slice( &original[ lower..upper ] );
+      }
+      ValueT::AtLeast( threshold ) =>
+      {
+        let lower = original.partition_point( | num | num < threshold );
+        vec.extend_from_slice( &original[ lower.. ] );
+      }
+      ValueT::AtMost( threshold ) =>
       {
-        vec.push( *num )
+        let upper = original.partition_point( | num | num <= threshold );
+        vec.extend_from_slice( &original[ ..upper ] );
       }
+      ValueT::Index( index ) => vec.extend( original.get( *index ) ),
+      ValueT::Any => {}
     }
   }
+
+  vec.sort();
+  vec.dedup();
   vec
 }
 
-/// Reduces original array by `preferred` vector and its size.
+/// Reduces original array by `preferred` vector, letting `choose` pick between the closest
+/// values below and above of a preferred value that `original` lacks.
 ///
-/// Returns vector with values that closest to `preferred` values.
-fn reduce_by_preferred( original : &Vec< i32 >, preferred : &Vec< Value > ) -> Vec< i32 > 
+/// `choose` receives bounds of the preferred value along with indices of the closest values
+/// below and above of it, and returns one of these indices.
+///
+/// Values are returned without duplicates, in order of their first occurrence.
+///
+/// Assumes `original` is sorted in ascending order.
+fn reduce_by_preferred_by< T, F >( original : &[ T ], preferred : &[ ValueT< T > ], choose : F ) -> Vec< T >
+where
+  T : Ord + Copy,
+  F : Fn( ( T, T ), usize, usize ) -> usize,
 {
+  debug_assert!( original.is_sorted(), "`original` must be sorted in ascending order" );
   if original.is_empty()
   {
     return vec![];
   }
-  let mut vec : Vec< i32 > = vec![];
+  let mut vec : Vec< T > = vec![];
   for value in preferred 
   {
-    if let Value::Number( num ) = value
+    let ( start, end ) = match value
     {
-      match original.binary_search( num ) 
+      ValueT::Any => continue,
+      ValueT::Number( num ) => ( *num, *num ),
+      ValueT::Range( start, end ) => normalize_range( *start, *end ),
+      ValueT::AtLeast( threshold ) =>
       {
-        Ok( _ ) => vec.push( *num ),
-        Err( index ) => 
-        {
-          if index == original.len()
-          {
-            vec.push( original[ index - 1 ] );
-          }
-          else if index < original.len()
-          {
-            vec.push( original[ index ] );
-          }
-        }
+        let index = original.partition_point( | num | num < threshold );
+        vec.push( original[ index.min( original.len() - 1 ) ] );
+        continue;
+      }
+      ValueT::AtMost( threshold ) =>
+      {
+        let index = original.partition_point( | num | num <= threshold );
+        vec.push( original[ index.saturating_sub( 1 ) ] );
+        continue;
+      }
+      ValueT::Index( index ) =>
+      {
+        vec.push( original[ ( *index ).min( original.len() - 1 ) ] );
+        continue;
       }
+    };
+    let lower = original.partition_point( | num | *num < start );
+    let upper = original.partition_point( | num | *num <= end );
+    if lower < upper
+    {
+      vec.extend_from_slice( &original[ lower..upper ] );
+    }
+    else if upper == original.len()
+    {
+      vec.push( original[ upper - 1 ] );
+    }
+    else if upper == 0
+    {
+      vec.push( original[ 0 ] );
+    }
+    else
+    {
+      vec.push( original[ choose( ( start, end ), upper - 1, upper ) ] );
     }
   }
 
-  vec.dedup();
+  let mut seen = BTreeSet::new();
+  vec.retain( | num | seen.insert( *num ) );
   vec
 }
 
-/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters.
+/// Reduces original array by `preferred` vector and its size.
 ///
-/// Returns vector of values that are both inside `avaliable` and `allowed` vectors, and
-/// has a size equal or less of `preferred` vector.
+/// Returns vector with values that closest to `preferred` values.
 ///
-/// Returns an empty vector if none of the `allowed` values are inside of `available` vector.
+/// Preferred range yields every value inside of it. If there are none, it yields the
+/// closest value above the range, or the closest value below it.
+///
+/// Preferred threshold yields the closest value on its side, or the closest value on the
+/// other side if there are none. Preferred index yields the value at the position, or the
+/// largest value if the position is out of bounds.
+///
+/// Returns copy of `original` if `preferred` contains `Value::Any`.
+///
+/// Assumes `original` is sorted in ascending order.
 ///
 /// # Examples
 ///
@@ -84,22 +776,162 @@ fn reduce_by_preferred( original : &Vec< i32 >, preferred : &Vec< Value > ) -> V
 ///
 /// assert_eq!
 /// (
-///   attempt
-///   (
-///     &vec![ 240, 360, 720 ],              // available values
-///     &vec![ Value::Number( 360 ), Value::Number( 720 ) ], // allowed filter vector
-///     &vec![ Value::Number( 1080 ) ]           // preferences
-///   ),
-///   vec![ 720 ]       // should return the value closest to 1080 and that is allowed
+///   reduce_by_preferred( &[ 240, 360, 720 ], &[ Value::Number( 300 ), Value::Number( 1080 ) ] ),
+///   vec![ 360, 720 ]
 /// );
-///
+/// ```
+pub fn reduce_by_preferred< T : Ord + Copy >( original : &[ T ], preferred : &[ ValueT< T > ] ) -> Vec< T > 
+{
+  if preferred.contains( &ValueT::Any )
+  {
+    return original.to_vec();
+  }
+  reduce_by_preferred_by( original, preferred, | _, _, above | above )
+}
+
+/// Reduces original array by `preferred` vector and its size.
+///
+/// Works like `reduce_by_preferred`, but `tie_break` decides which of the closest values
+/// is returned when `original` has values both below and above of the preferred value.
+///
+/// Returns copy of `original` if `preferred` contains `Value::Any`.
+///
+/// Assumes `original` is sorted in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let original = [ 360, 720 ];
+/// let preferred = [ Value::Number( 480 ) ];
+/// assert_eq!( reduce_by_preferred_with( &original, &preferred, TieBreak::PreferHigher ), vec![ 720 ] );
+/// assert_eq!( reduce_by_preferred_with( &original, &preferred, TieBreak::PreferLower ), vec![ 360 ] );
+/// assert_eq!( reduce_by_preferred_with( &original, &preferred, TieBreak::PreferNearest ), vec![ 360 ] );
+/// ```
+pub fn reduce_by_preferred_with< T : Distance >
+(
+  original : &[ T ],
+  preferred : &[ ValueT< T > ],
+  tie_break : TieBreak
+) -> Vec< T >
+{
+  if preferred.contains( &ValueT::Any )
+  {
+    return original.to_vec();
+  }
+  reduce_by_preferred_by
+  (
+    original,
+    preferred,
+    | ( start, end ), below, above | match tie_break
+    {
+      TieBreak::PreferLower => below,
+      TieBreak::PreferHigher => above,
+      TieBreak::PreferNearest =>
+      {
+        if original[ above ].distance( end ) < original[ below ].distance( start )
+        {
+          above
+        }
+        else
+        {
+          below
+        }
+      }
+    }
+  )
+}
+
+/// Reduces original array by weighted `preferred` vector.
+///
+/// Every preference contributes its weight to the values it selects, following the rules of
+/// `reduce_by_preferred`. Returns selected values ordered by descending accumulated weight.
+/// Values with equal accumulated weight are ordered by descending weight of their strongest
+/// preference, and then by ascending value.
+///
+/// Assumes `original` is sorted in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// assert_eq!
+/// (
+///   reduce_by_weighted_preferred
+///   (
+///     &[ 480, 720, 1080 ],
+///     &[
+///       WeightedValue { value : Value::Number( 720 ), weight : 0.5 },
+///       WeightedValue { value : Value::Number( 1080 ), weight : 2.0 },
+///       WeightedValue { value : Value::Number( 600 ), weight : 0.5 },
+///     ]
+///   ),
+///   vec![ 1080, 720 ]
+/// );
+/// ```
+pub fn reduce_by_weighted_preferred< T : Ord + Copy >
+(
+  original : &[ T ],
+  preferred : &[ WeightedValue< T > ]
+) -> Vec< T >
+{
+  let mut weights : BTreeMap< T, ( f32, f32 ) > = BTreeMap::new();
+  for WeightedValue { value, weight } in preferred
+  {
+    for num in reduce_by_preferred( original, core::slice::from_ref( value ) )
+    {
+      let ( total, strongest ) = weights.entry( num ).or_insert( ( 0.0, f32::NEG_INFINITY ) );
+      *total += weight;
+      *strongest = strongest.max( *weight );
+    }
+  }
+
+  let mut ranked : Vec< _ > = weights.into_iter().collect();
+  ranked.sort_by
+  (
+    | ( a, ( a_total, a_strongest ) ), ( b, ( b_total, b_strongest ) ) |
+    b_total.total_cmp( a_total )
+    .then( b_strongest.total_cmp( a_strongest ) )
+    .then( a.cmp( b ) )
+  );
+  ranked.into_iter().map( | ( num, _ ) | num ).collect()
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters.
+///
+/// Returns vector of values that are both inside `avaliable` and `allowed` vectors, and
+/// has a size equal or less of `preferred` vector.
+///
+/// Returns an empty vector if none of the `allowed` values are inside of `available` vector.
+/// Use `attempt_checked` to tell apart malformed input from an empty result.
+///
+/// Unsorted `available` vector is sorted into a copy before filtering.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// assert_eq!
+/// (
+///   attempt
+///   (
+///     &[ 240, 360, 720 ],              // available values
+///     &[ Value::Number( 360 ), Value::Number( 720 ) ], // allowed filter vector
+///     &[ Value::Number( 1080 ) ]           // preferences
+///   ),
+///   vec![ 720 ]       // should return the value closest to 1080 and that is allowed
+/// );
+///
 /// assert_eq!
 /// (
 ///   attempt
 ///   (
-///     &vec![ 240, 360, 720 ],
-///     &vec![ Value::Number( 360 ), Value::Any ],
-///     &vec![ Value::Number( 360 ), Value::Number( 720 ) ]
+///     &[ 240, 360, 720 ],
+///     &[ Value::Number( 360 ), Value::Any ],
+///     &[ Value::Number( 360 ), Value::Number( 720 ) ]
 ///   ),
 ///   vec![ 360, 720 ]
 /// );
@@ -107,254 +939,6118 @@ fn reduce_by_preferred( original : &Vec< i32 >, preferred : &Vec< Value > ) -> V
 /// (
 ///   attempt
 ///   (
-///     &vec![ 240, 360, 720 ],
-///     &vec![ Value::Number( 240 ), Value::Number( 360 ), Value::Number( 720 ) ],
-///     &vec![ Value::Any, Value::Number( 720 ) ]
+///     &[ 240, 360, 720 ],
+///     &[ Value::Number( 240 ), Value::Number( 360 ), Value::Number( 720 ) ],
+///     &[ Value::Any, Value::Number( 720 ) ]
 ///   ),
 ///   vec![ 240, 360, 720 ]
 /// );
 /// ```
-pub fn attempt(available : &Vec< i32 >, allowed : &Vec< Value >, preferred : &Vec< Value >) -> Vec< i32 >
+pub fn attempt< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Vec< T >
+{
+  attempt_with_order( available, allowed, preferred, OutputOrder::AsPreferred )
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters.
+///
+/// Works like `attempt`, but returns values in the given `order`.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// assert_eq!
+/// (
+///   attempt_with_order
+///   (
+///     &[ 240, 360, 720 ],
+///     &[ Value::Any ],
+///     &[ Value::Number( 720 ), Value::Number( 240 ) ],
+///     OutputOrder::Ascending
+///   ),
+///   vec![ 240, 720 ]
+/// );
+/// ```
+pub fn attempt_with_order< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  order : OutputOrder
+) -> Vec< T >
+{
+  let mut result = attempt_sorted_unchecked( &sorted( available ), allowed, preferred );
+
+  match order
+  {
+    OutputOrder::AsPreferred => {}
+    OutputOrder::Ascending => result.sort(),
+    OutputOrder::Descending => result.sort_by( | a, b | b.cmp( a ) ),
+  }
+  result
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters.
+///
+/// Fast path of `attempt` which assumes `available` is sorted in ascending order and never
+/// checks it in release builds. Unsorted `available` vector produces meaningless result.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// assert_eq!
+/// (
+///   attempt_sorted_unchecked
+///   (
+///     &[ 240, 360, 720 ],
+///     &[ Value::Number( 360 ), Value::Number( 720 ) ],
+///     &[ Value::Number( 1080 ) ]
+///   ),
+///   vec![ 720 ]
+/// );
+/// ```
+pub fn attempt_sorted_unchecked< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Vec< T >
 {
-  if allowed.contains( &Value::Any ) && preferred.contains( &Value::Any ) 
+  debug_assert!( available.is_sorted(), "`available` must be sorted in ascending order" );
+  if allowed.contains( &ValueT::Any ) && preferred.contains( &ValueT::Any )
   {
     return available.to_vec();
   }
-  if allowed.contains( &Value::Any ) 
+  if allowed.contains( &ValueT::Any )
+  {
+    return reduce_by_preferred( available, preferred );
+  }
+  if preferred.contains( &ValueT::Any )
+  {
+    return reduce_by_allowed( available, allowed );
+  }
+  let available = &reduce_by_allowed( available, allowed );
+  reduce_by_preferred( available, preferred )
+}
+
+/// Resolves `preferred` values against sorted `ladder` like `reduce_by_preferred`, pairing
+/// every value with the description of the first preference selecting it, made by `describe`
+/// from the preference and the position of the value in the ladder.
+fn describe_selection< T, X, F >( ladder : &[ T ], preferred : &[ ValueT< T > ], describe : F ) -> Vec< ( T, X ) >
+where
+  T : Ord + Copy,
+  F : Fn( &ValueT< T >, usize ) -> X,
+{
+  if preferred.contains( &ValueT::Any )
+  {
+    return ladder.iter().enumerate().map( | ( index, num ) | ( *num, describe( &ValueT::Any, index ) ) ).collect();
+  }
+  let mut seen = BTreeSet::new();
+  let mut vec = vec![];
+  for value in preferred
+  {
+    for num in reduce_by_preferred( ladder, core::slice::from_ref( value ) )
+    {
+      if seen.insert( num )
+      {
+        vec.push( ( num, describe( value, ladder.partition_point( | other | *other < num ) ) ) );
+      }
+    }
+  }
+  vec
+}
+
+/// Resolves `preferred` value against `available` values that pass `is_allowed`, following the
+/// same rules as `reduce_by_preferred`.
+///
+/// Returns bounds of indices to yield the allowed ones of, along with the index to yield if
+/// there are none.
+fn resolve_bounds< T, F >( available : &[ T ], value : &ValueT< T >, is_allowed : F ) -> ( usize, usize, Option< usize > )
+where
+  T : Ord + Copy,
+  F : Fn( usize ) -> bool,
+{
+  let above = | from : usize | ( from..available.len() ).find( | index | is_allowed( *index ) );
+  let below = | to : usize | ( 0..to ).rev().find( | index | is_allowed( *index ) );
+  let around = | ( start, end ) : ( T, T ) |
+  {
+    let lower = available.partition_point( | num | *num < start );
+    let upper = available.partition_point( | num | *num <= end );
+    let fallback = if ( lower..upper ).any( &is_allowed )
+    {
+      None
+    }
+    else
+    {
+      above( upper ).or_else( || below( lower ) )
+    };
+    ( lower, upper, fallback )
+  };
+  match value
+  {
+    ValueT::Any => ( 0, 0, None ),
+    ValueT::Number( num ) => around( ( *num, *num ) ),
+    ValueT::Range( start, end ) => around( normalize_range( *start, *end ) ),
+    ValueT::AtLeast( threshold ) =>
+    {
+      let index = available.partition_point( | num | num < threshold );
+      ( index, index, above( index ).or_else( || below( index ) ) )
+    }
+    ValueT::AtMost( threshold ) =>
+    {
+      let index = available.partition_point( | num | num <= threshold );
+      ( index, index, below( index ).or_else( || above( index ) ) )
+    }
+    ValueT::Index( position ) =>
+    {
+      let found = ( 0..available.len() )
+      .filter( | index | is_allowed( *index ) )
+      .take( position.saturating_add( 1 ) )
+      .last();
+      ( 0, 0, found )
+    }
+  }
+}
+
+/// Returns sorted and disjoint spans of positions of a ladder with `len` values, covering the
+/// values `allowed` allows.
+///
+/// `below` and `up_to` count ladder values less than and not greater than the given one, and
+/// `value` returns the value at a position.
+fn allowed_spans< T, B, U, V >( len : usize, allowed : &[ ValueT< T > ], below : B, up_to : U, value : V ) -> Vec< ( usize, usize ) >
+where
+  T : Ord + Copy,
+  B : Fn( T ) -> usize,
+  U : Fn( T ) -> usize,
+  V : Fn( usize ) -> T,
+{
+  let mut spans : Vec< ( usize, usize ) > = allowed.iter()
+  .map( | allowed | match allowed
+  {
+    ValueT::Any => ( 0, len ),
+    ValueT::Number( num ) => ( below( *num ), up_to( *num ) ),
+    ValueT::Range( start, end ) =>
+    {
+      let ( start, end ) = normalize_range( *start, *end );
+      ( below( start ), up_to( end ) )
+    }
+    ValueT::AtLeast( threshold ) => ( below( *threshold ), len ),
+    ValueT::AtMost( threshold ) => ( 0, up_to( *threshold ) ),
+    ValueT::Index( position ) if *position < len => ( below( value( *position ) ), up_to( value( *position ) ) ),
+    ValueT::Index( _ ) => ( 0, 0 ),
+  })
+  .filter( | ( start, end ) | start < end )
+  .collect();
+  spans.sort();
+  let mut merged : Vec< ( usize, usize ) > = vec![];
+  for ( start, end ) in spans
+  {
+    match merged.last_mut()
+    {
+      Some( last ) if start <= last.1 => last.1 = last.1.max( end ),
+      _ => merged.push( ( start, end ) ),
+    }
+  }
+  merged
+}
+
+/// Iterator returned by `attempt_iter`.
+struct AttemptIter< 'a, T >
+{
+  available : &'a [ T ],
+  preferred : &'a [ ValueT< T > ],
+  /// Spans of indices of allowed values, as returned by `allowed_spans`.
+  spans : Vec< ( usize, usize ) >,
+  /// Whether every available value is yielded, duplicates included.
+  everything : bool,
+  /// Position of the next preference to resolve.
+  position : usize,
+  /// Indices of the current preference to yield the allowed ones of.
+  pending : core::ops::Range< usize >,
+  /// Index of the current preference to yield if none of `pending` is allowed.
+  fallback : Option< usize >,
+  /// Indices already yielded by preferences.
+  seen : BTreeSet< usize >,
+}
+
+impl< T : Ord + Copy > AttemptIter< '_, T >
+{
+  /// Whether the value at `index` is allowed. Only the first of equal values is taken into
+  /// account, so every value is yielded once.
+  fn is_allowed( &self, index : usize ) -> bool
+  {
+    let after = self.spans.partition_point( | ( start, _ ) | *start <= index );
+    ( index == 0 || self.available[ index - 1 ] != self.available[ index ] )
+    && after > 0 && index < self.spans[ after - 1 ].1
+  }
+}
+
+impl< T : Ord + Copy > Iterator for AttemptIter< '_, T >
+{
+  type Item = T;
+
+  fn next( &mut self ) -> Option< T >
+  {
+    loop
+    {
+      let index = if let Some( index ) = self.pending.next()
+      {
+        if !self.everything && !self.is_allowed( index )
+        {
+          continue;
+        }
+        index
+      }
+      else if let Some( index ) = self.fallback.take()
+      {
+        index
+      }
+      else
+      {
+        let value = self.preferred.get( self.position )?;
+        self.position += 1;
+        let ( lower, upper, fallback ) = resolve_bounds( self.available, value, | index | self.is_allowed( index ) );
+        self.pending = lower..upper;
+        self.fallback = fallback;
+        continue;
+      };
+      if self.everything || self.preferred.is_empty() || self.seen.insert( index )
+      {
+        return Some( self.available[ index ] );
+      }
+    }
+  }
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters lazily.
+///
+/// Works like `attempt_sorted_unchecked`, but returns an iterator borrowing the inputs, which
+/// computes values only when they are requested. Allocates only spans of allowed values and
+/// the set of values already yielded, never the whole ladder.
+///
+/// Assumes `available` is sorted in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720, 1080 ];
+/// let allowed = [ Value::Range( 240, 720 ) ];
+/// let preferred = [ Value::Number( 1080 ), Value::Number( 240 ) ];
+/// let mut iter = attempt_iter( &available, &allowed, &preferred );
+/// assert_eq!( iter.next(), Some( 720 ) );
+/// assert_eq!( iter.next(), Some( 240 ) );
+/// assert_eq!( iter.next(), None );
+/// ```
+pub fn attempt_iter< 'a, T : Ord + Copy >
+(
+  available : &'a [ T ],
+  allowed : &'a [ ValueT< T > ],
+  preferred : &'a [ ValueT< T > ]
+) -> impl Iterator< Item = T > + 'a
+{
+  debug_assert!( available.is_sorted(), "`available` must be sorted in ascending order" );
+  let allow_any = allowed.contains( &ValueT::Any );
+  let prefer_any = preferred.contains( &ValueT::Any );
+  let spans = allowed_spans
+  (
+    available.len(),
+    allowed,
+    | num | available.partition_point( | other | *other < num ),
+    | num | available.partition_point( | other | *other <= num ),
+    | position | available[ position ],
+  );
+  AttemptIter
+  {
+    available,
+    preferred : if prefer_any { &[] } else { preferred },
+    spans,
+    everything : prefer_any && allow_any,
+    position : 0,
+    pending : if prefer_any { 0..available.len() } else { 0..0 },
+    fallback : None,
+    seen : BTreeSet::new(),
+  }
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters into `out`.
+///
+/// Works like `attempt`, but clears and fills the caller's buffer instead of returning a new
+/// vector, so a single buffer can be reused across calls. Never allocates the result, unless
+/// `out` lacks capacity for it, and otherwise allocates only what `attempt_iter` does and a
+/// sorted copy of unsorted `available`.
+///
+/// Returns the number of values written to `out`.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let mut out = Vec::with_capacity( 4 );
+/// assert_eq!( attempt_into( &[ 240, 360, 720 ], &[ Value::Any ], &[ Value::Number( 300 ) ], &mut out ), 1 );
+/// assert_eq!( out, vec![ 360 ] );
+/// assert_eq!( attempt_into( &[ 240, 360, 720 ], &[ Value::Number( 1080 ) ], &[ Value::Any ], &mut out ), 0 );
+/// assert!( out.is_empty() );
+/// ```
+pub fn attempt_into< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  out : &mut Vec< T >
+) -> usize
+{
+  out.clear();
+  out.extend( attempt_iter( &sorted( available ), allowed, preferred ) );
+  out.len()
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters.
+///
+/// Works like `attempt`, but tells apart malformed input from a legitimately empty result.
+///
+/// # Errors
+///
+/// Returns `AttemptError::UnsortedInput` if `available` is not sorted in ascending order,
+/// `AttemptError::EmptyAllowed` if `allowed` is empty and `AttemptError::NoMatch` if none of the
+/// values satisfy the filters. Order of `allowed` does not matter and order of `preferred` is
+/// their priority, so neither is checked.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// assert_eq!
+/// (
+///   attempt_checked( &[ 240, 360, 720 ], &[ Value::Any ], &[ Value::Number( 360 ) ] ),
+///   Ok( vec![ 360 ] )
+/// );
+/// assert_eq!
+/// (
+///   attempt_checked( &[ 720, 360 ], &[ Value::Any ], &[ Value::Number( 360 ) ] ),
+///   Err( AttemptError::UnsortedInput )
+/// );
+/// assert_eq!
+/// (
+///   attempt_checked( &[ 240 ], &[ Value::Number( 360 ) ], &[ Value::Any ] ),
+///   Err( AttemptError::NoMatch )
+/// );
+/// ```
+pub fn attempt_checked< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Result< Vec< T >, AttemptError >
+{
+  if !available.is_sorted()
+  {
+    return Err( AttemptError::UnsortedInput );
+  }
+  if allowed.is_empty()
+  {
+    return Err( AttemptError::EmptyAllowed );
+  }
+
+  let result = attempt_sorted_unchecked( available, allowed, preferred );
+  if result.is_empty()
+  {
+    return Err( AttemptError::NoMatch );
+  }
+  Ok( result )
+}
+
+/// Selects a single value, sticking to the `previous` one to avoid churn.
+///
+/// Takes the first value `attempt` returns for the inputs, but returns `previous` instead if it
+/// is still available and allowed, and the new value is within `jitter` of it. Returns
+/// `previous` as well if `attempt` returns no values.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 360, 480, 720, 1080 ];
+/// assert_eq!( select_stable( &available, &[ Value::Any ], &[ Value::Number( 720 ) ], 480, 300 ), 480 );
+/// assert_eq!( select_stable( &available, &[ Value::Any ], &[ Value::Number( 1080 ) ], 480, 300 ), 1080 );
+/// ```
+pub fn select_stable< T : Distance >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  previous : T,
+  jitter : u64
+) -> T
+{
+  let available = sorted( available );
+  let Some( &selected ) = attempt_sorted_unchecked( &available, allowed, preferred ).first() else
+  {
+    return previous;
+  };
+  let selectable = reduce_by_allowed( &available, allowed ).binary_search( &previous ).is_ok();
+  if selectable && selected.distance( previous ) <= jitter
+  {
+    previous
+  }
+  else
+  {
+    selected
+  }
+}
+
+/// Restricts already computed `selection` to values of the `whitelist`.
+///
+/// Returns values of `selection` that `whitelist` has, in order of `selection`.
+///
+/// Assumes `whitelist` is sorted in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// assert_eq!( restrict( &[ 720, 240, 1080 ], &[ 240, 360, 1080 ] ), vec![ 240, 1080 ] );
+/// ```
+pub fn restrict< T : Ord + Copy >( selection : &[ T ], whitelist : &[ T ] ) -> Vec< T >
+{
+  debug_assert!( whitelist.is_sorted(), "`whitelist` must be sorted in ascending order" );
+  selection.iter()
+  .copied()
+  .filter( | num | whitelist.binary_search( num ).is_ok() )
+  .collect()
+}
+
+/// Selects a single value, moving from the `previous` one by at most one step of the ladder.
+///
+/// The ladder consists of available values that are allowed. Takes the first value `attempt`
+/// returns for the inputs as the target, and returns the closest value of the ladder from
+/// `previous` towards the target. Returns `previous` if it equals the target, or if `attempt`
+/// returns no values.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 480, 720, 1080 ];
+/// assert_eq!( select_gradual( &available, &[ Value::Any ], &[ Value::Number( 1080 ) ], 360 ), 480 );
+/// assert_eq!( select_gradual( &available, &[ Value::Any ], &[ Value::Number( 240 ) ], 720 ), 480 );
+/// ```
+pub fn select_gradual< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  previous : T
+) -> T
+{
+  let available = sorted( available );
+  let Some( &target ) = attempt_sorted_unchecked( &available, allowed, preferred ).first() else
+  {
+    return previous;
+  };
+  let ladder = reduce_by_allowed( &available, allowed );
+  match target.cmp( &previous )
+  {
+    core::cmp::Ordering::Greater => ladder[ ladder.partition_point( | num | *num <= previous ) ],
+    core::cmp::Ordering::Less => ladder[ ladder.partition_point( | num | *num < previous ) - 1 ],
+    core::cmp::Ordering::Equal => previous,
+  }
+}
+
+/// Measures how far the selection is from the preferences.
+///
+/// Every `Value::Number` preference is resolved against available values that are allowed,
+/// following the rules of `reduce_by_preferred`. Returns the sum of distances between the
+/// preferences and the values they resolve to, so exact matches cost nothing. Other kinds of
+/// preferences describe sets of acceptable values rather than a target and cost nothing too.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// assert_eq!( selection_cost( &available, &[ Value::Any ], &[ Value::Number( 360 ) ] ), 0 );
+/// assert_eq!( selection_cost( &available, &[ Value::Any ], &[ Value::Number( 300 ), Value::Number( 1080 ) ] ), 420 );
+/// ```
+pub fn selection_cost< T : Distance >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> u64
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  preferred.iter()
+  .filter_map( | value | match value
+  {
+    ValueT::Number( num ) => resolve_number( &ladder, *num ).map( | index | ladder[ index ].distance( *num ) ),
+    _ => None,
+  })
+  .sum()
+}
+
+/// Header of the rows produced by `to_csv_row`.
+#[ cfg( feature = "csv" ) ]
+pub const CSV_HEADER : &str = "ladder_size,allowed_count,preferred_count,selected,cost";
+
+/// Formats the selection for the inputs as a row of comma separated values.
+///
+/// The row has the columns of `CSV_HEADER`: the number of available values, the number of
+/// available values that are allowed, the number of preferences, the selected values separated
+/// by semicolons and the `selection_cost`. The row has no trailing line break.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// assert_eq!
+/// (
+///   to_csv_row( &[ 240, 360, 720 ], &[ Value::Number( 360 ), Value::Number( 720 ) ], &[ Value::Number( 1080 ) ] ),
+///   "3,2,1,720,360"
+/// );
+/// ```
+#[ cfg( feature = "csv" ) ]
+pub fn to_csv_row< T : Distance + core::fmt::Display >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> alloc::string::String
+{
+  use core::fmt::Write;
+
+  let allowed_count = reduce_by_allowed( &sorted( available ), allowed ).len();
+  let selected = attempt( available, allowed, preferred );
+  let mut row = alloc::format!( "{},{},{},", available.len(), allowed_count, preferred.len() );
+  for ( index, num ) in selected.iter().enumerate()
+  {
+    if index > 0
+    {
+      row.push( ';' );
+    }
+    let _ = write!( row, "{num}" );
+  }
+  let _ = write!( row, ",{}", selection_cost( available, allowed, preferred ) );
+  row
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, treating
+/// positions of preferred `Value::Index` as cyclic.
+///
+/// Works like `attempt`, but a preferred position out of bounds wraps around instead of being
+/// clamped, so `Value::Index( i )` selects the value at `i % len` among `len` allowed values.
+///
+/// Positions are `usize`, so there are no negative ones. Note that `usize::MAX % len` is not
+/// `len - 1` in general, so to step back from position `i` use `( i + len - 1 ) % len` rather
+/// than `i.wrapping_sub( 1 )`.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// assert_eq!( attempt_cyclic_index( &available, &[ Value::Any ], &[ Value::Index( 4 ) ] ), vec![ 360 ] );
+/// assert_eq!( attempt( &available, &[ Value::Any ], &[ Value::Index( 4 ) ] ), vec![ 720 ] );
+/// ```
+pub fn attempt_cyclic_index< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Vec< T >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  if ladder.is_empty()
+  {
+    return vec![];
+  }
+  let preferred : Vec< _ > = preferred.iter()
+  .map( | value | match value
+  {
+    ValueT::Index( index ) => ValueT::Index( index % ladder.len() ),
+    other => *other,
+  })
+  .collect();
+  reduce_by_preferred( &ladder, &preferred )
+}
+
+/// Compares the `old` selection with the `new` one.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let diff = diff_selections( &[ 360, 720 ], &[ 720, 1080 ] );
+/// assert_eq!( diff.added, vec![ 1080 ] );
+/// assert_eq!( diff.removed, vec![ 360 ] );
+/// assert_eq!( diff.kept, vec![ 720 ] );
+/// ```
+pub fn diff_selections< T : Ord + Copy >( old : &[ T ], new : &[ T ] ) -> SelectionDiff< T >
+{
+  let old_set : BTreeSet< _ > = old.iter().collect();
+  let new_set : BTreeSet< _ > = new.iter().collect();
+  let ( kept, added ) = new.iter().partition( | num | old_set.contains( num ) );
+  SelectionDiff
+  {
+    added,
+    removed : old.iter().copied().filter( | num | !new_set.contains( num ) ).collect(),
+    kept,
+  }
+}
+
+/// Shows how the selection for the same filters shifts when the ladder changes.
+///
+/// Runs `attempt` on both `old_ladder` and `new_ladder` and compares the results with
+/// `diff_selections`.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let preferred = [ Value::Number( 480 ), Value::Number( 720 ) ];
+/// let diff = selection_delta( &[ 360, 720 ], &[ 360, 480, 720 ], &[ Value::Any ], &preferred );
+/// assert_eq!( diff.added, vec![ 480 ] );
+/// assert_eq!( diff.removed, vec![] );
+/// assert_eq!( diff.kept, vec![ 720 ] );
+/// ```
+pub fn selection_delta< T : Ord + Copy >
+(
+  old_ladder : &[ T ],
+  new_ladder : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> SelectionDiff< T >
+{
+  diff_selections( &attempt( old_ladder, allowed, preferred ), &attempt( new_ladder, allowed, preferred ) )
+}
+
+/// Reduces `avaliable` vector by `allowed` vector filter and preferences with explicit fallbacks.
+///
+/// Every preference yields its primary value if it is available and allowed, otherwise its
+/// fallback value under the same condition, otherwise nothing. Unlike `attempt`, values are
+/// matched exactly and never rounded to the nearest one.
+///
+/// Returns values in order of `prefs` without duplicates.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let prefs = [ PreferenceWithFallback { primary : 1080, fallback : 480 } ];
+/// assert_eq!( attempt_with_fallbacks( &[ 360, 480, 720 ], &[ Value::Any ], &prefs ), vec![ 480 ] );
+/// assert_eq!( attempt_with_fallbacks( &[ 360, 720 ], &[ Value::Any ], &prefs ), vec![] );
+/// ```
+pub fn attempt_with_fallbacks< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  prefs : &[ PreferenceWithFallback< T > ]
+) -> Vec< T >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  let mut seen = BTreeSet::new();
+  prefs.iter()
+  .filter_map( | pref |
+  {
+    [ pref.primary, pref.fallback ].into_iter().find( | num | ladder.binary_search( num ).is_ok() )
+  })
+  .filter( | num | seen.insert( *num ) )
+  .collect()
+}
+
+/// Reduces `avaliable` vector by `allowed` vector filter lazily.
+///
+/// Works like `reduce_by_allowed`, but returns an iterator borrowing the inputs, which yields
+/// allowed values in ascending order only when they are requested.
+///
+/// Assumes `available` is sorted in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720, 1080 ];
+/// let allowed = [ Value::AtLeast( 300 ) ];
+/// assert_eq!( allowed_iter( &available, &allowed ).find( | num | num % 240 == 0 ), Some( 720 ) );
+/// ```
+pub fn allowed_iter< 'a, T : Ord + Copy >
+(
+  available : &'a [ T ],
+  allowed : &'a [ ValueT< T > ]
+) -> impl Iterator< Item = T > + 'a
+{
+  debug_assert!( available.is_sorted(), "`available` must be sorted in ascending order" );
+  let allow_any = allowed.contains( &ValueT::Any );
+  ( 0..available.len() )
+  .filter( move | index |
+  {
+    allow_any ||
+    (
+      ( *index == 0 || available[ index - 1 ] != available[ *index ] )
+      && allowed.iter().any( | value | value.allows( available, *index ) )
+    )
+  })
+  .map( move | index | available[ index ] )
+}
+
+/// Quantizes continuous `target` to the nearest value of `available` vector.
+///
+/// Values are compared by absolute distance to `target`. When `target` is exactly halfway
+/// between two values, the greater one is selected, like with `TieBreak::PreferHigher`.
+/// Returns `None` if `available` is empty or `target` is NaN.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// assert_eq!( quantize( &available, 310.5 ), Some( 360 ) );
+/// assert_eq!( quantize( &available, 300.0 ), Some( 360 ) );
+/// assert_eq!( quantize( &available, 299.9 ), Some( 240 ) );
+/// assert_eq!( quantize( &available, f64::INFINITY ), Some( 720 ) );
+/// ```
+pub fn quantize( available : &[ i32 ], target : f64 ) -> Option< i32 >
+{
+  if target.is_nan()
+  {
+    return None;
+  }
+  let available = sorted( available );
+  let index = available.partition_point( | num | f64::from( *num ) < target );
+  match ( index.checked_sub( 1 ).map( | index | available[ index ] ), available.get( index ) )
+  {
+    ( Some( lower ), Some( &upper ) ) =>
+    {
+      if target - f64::from( lower ) < f64::from( upper ) - target { Some( lower ) } else { Some( upper ) }
+    }
+    ( lower, upper ) => lower.or( upper.copied() ),
+  }
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, ranking
+/// preferences by decaying influence.
+///
+/// Preference at position `i` has influence `decay.powi( i )`, so with `decay` below one earlier
+/// preferences dominate, and with `decay` above one later ones do. Every preference resolves
+/// like with `attempt`, and a value selected by several preferences takes the highest influence
+/// among them. Returns values ordered by descending influence, values of equal influence in
+/// order of `preferred`.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let preferred = [ Value::Number( 240 ), Value::Number( 720 ) ];
+/// assert_eq!( attempt_decayed( &available, &[ Value::Any ], &preferred, 0.5 ), vec![ 240, 720 ] );
+/// assert_eq!( attempt_decayed( &available, &[ Value::Any ], &preferred, 2.0 ), vec![ 720, 240 ] );
+/// ```
+pub fn attempt_decayed< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  decay : f64
+) -> Vec< T >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  let mut ranked : Vec< ( T, f64 ) > = vec![];
+  let mut positions = BTreeMap::new();
+  let mut influence = 1.0;
+  for value in preferred
+  {
+    for num in reduce_by_preferred( &ladder, core::slice::from_ref( value ) )
+    {
+      let position = *positions.entry( num ).or_insert_with( ||
+      {
+        ranked.push( ( num, influence ) );
+        ranked.len() - 1
+      });
+      if ranked[ position ].1 < influence
+      {
+        ranked[ position ].1 = influence;
+      }
+    }
+    influence *= decay;
+  }
+  ranked.sort_by( | a, b | b.1.total_cmp( &a.1 ) );
+  ranked.into_iter().map( | ( num, _ ) | num ).collect()
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, treating its
+/// duplicates according to `policy`.
+///
+/// Selection runs on the ladder of distinct available values, so duplicates never change which
+/// values are selected nor their order. With `DuplicatePolicy::Collapse` every selected value is
+/// returned once. With `DuplicatePolicy::Preserve` every selected value is repeated as many
+/// times as it occurs in `available`, its copies next to each other.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 720, 360, 720, 240 ];
+/// let preferred = [ Value::Number( 720 ), Value::Number( 360 ) ];
+/// assert_eq!( attempt_dup_policy( &available, &[ Value::Any ], &preferred, DuplicatePolicy::Collapse ), vec![ 720, 360 ] );
+/// assert_eq!
+/// (
+///   attempt_dup_policy( &available, &[ Value::Any ], &preferred, DuplicatePolicy::Preserve ),
+///   vec![ 720, 720, 360 ]
+/// );
+/// ```
+pub fn attempt_dup_policy< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  policy : DuplicatePolicy
+) -> Vec< T >
+{
+  let available = sorted( available );
+  let mut ladder = available.to_vec();
+  ladder.dedup();
+  let selected = attempt_sorted_unchecked( &ladder, allowed, preferred );
+  match policy
+  {
+    DuplicatePolicy::Collapse => selected,
+    DuplicatePolicy::Preserve =>
+    {
+      selected.into_iter()
+      .flat_map( | num |
+      {
+        let lower = available.partition_point( | other | *other < num );
+        let upper = available.partition_point( | other | *other <= num );
+        core::iter::repeat_n( num, upper - lower )
+      })
+      .collect()
+    }
+  }
+}
+
+/// Lists positions of `preferred` values that select the `rung`.
+///
+/// Every preference is resolved against available values that are allowed, following the rules
+/// of `reduce_by_preferred`, so a preference selects the `rung` whether it matches it exactly or
+/// falls back to it. Returns positions in ascending order, or nothing if the `rung` is not
+/// available or not allowed.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let preferred = [ Value::Number( 720 ), Value::Number( 240 ), Value::Number( 1080 ) ];
+/// assert_eq!( preferences_for( &available, &[ Value::Any ], &preferred, 720 ), vec![ 0, 2 ] );
+/// ```
+pub fn preferences_for< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  rung : T
+) -> Vec< usize >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  preferred.iter()
+  .enumerate()
+  .filter( | ( _, value ) | reduce_by_preferred( &ladder, core::slice::from_ref( *value ) ).contains( &rung ) )
+  .map( | ( position, _ ) | position )
+  .collect()
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, skipping
+/// expired allowed values.
+///
+/// Allowed value is expired if its `expires_at` is not greater than `now`. Works like `attempt`
+/// with the allowed values that are not expired.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let allowed =
+/// [
+///   TimedAllowed { value : Value::Number( 720 ), expires_at : 100 },
+///   TimedAllowed { value : Value::Number( 360 ), expires_at : 200 },
+/// ];
+/// let preferred = [ Value::Number( 720 ) ];
+/// assert_eq!( attempt_timed( &[ 360, 720 ], &allowed, &preferred, 50 ), vec![ 720 ] );
+/// assert_eq!( attempt_timed( &[ 360, 720 ], &allowed, &preferred, 100 ), vec![ 360 ] );
+/// ```
+pub fn attempt_timed< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ TimedAllowed< T > ],
+  preferred : &[ ValueT< T > ],
+  now : u64
+) -> Vec< T >
+{
+  let allowed : Vec< _ > = allowed.iter()
+  .filter( | timed | timed.expires_at > now )
+  .map( | timed | timed.value )
+  .collect();
+  attempt( available, &allowed, preferred )
+}
+
+/// Encodes values `attempt` selects as a bitmask over positions of `avaliable` vector.
+///
+/// Bit `i % 64` of word `i / 64` is set if `available[ i ]` is selected. Bits of all equal
+/// values are set together. Returns as many words as needed to cover `available`.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let mask = attempt_mask( &available, &[ Value::Any ], &[ Value::Number( 240 ), Value::Number( 720 ) ] );
+/// assert_eq!( mask, vec![ 0b101 ] );
+/// assert_eq!( mask_to_values( &available, &mask ), vec![ 240, 720 ] );
+/// ```
+pub fn attempt_mask< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Vec< u64 >
+{
+  let selected : BTreeSet< _ > = attempt( available, allowed, preferred ).into_iter().collect();
+  let mut mask = vec![ 0; available.len().div_ceil( 64 ) ];
+  for ( index, num ) in available.iter().enumerate()
+  {
+    if selected.contains( num )
+    {
+      mask[ index / 64 ] |= 1 << ( index % 64 );
+    }
+  }
+  mask
+}
+
+/// Decodes values of `avaliable` vector from the bitmask produced by `attempt_mask`.
+///
+/// Returns values at positions with set bits, in order of `available`. Bits past the end of
+/// `available` are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// assert_eq!( mask_to_values( &[ 240, 360, 720 ], &[ 0b110 ] ), vec![ 360, 720 ] );
+/// ```
+pub fn mask_to_values< T : Copy >( available : &[ T ], mask : &[ u64 ] ) -> Vec< T >
+{
+  available.iter()
+  .enumerate()
+  .filter( | ( index, _ ) | mask.get( index / 64 ).is_some_and( | word | word >> ( index % 64 ) & 1 == 1 ) )
+  .map( | ( _, num ) | *num )
+  .collect()
+}
+
+/// Selects values that never go below the highest value selected before.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let mut selector = RatchetSelector::new();
+/// assert_eq!( selector.select( &available, &[ Value::Any ], &[ Value::Number( 360 ) ] ), Some( 360 ) );
+/// assert_eq!( selector.select( &available, &[ Value::Any ], &[ Value::Number( 240 ) ] ), Some( 360 ) );
+/// assert_eq!( selector.select( &available, &[ Value::AtMost( 240 ) ], &[ Value::Any ] ), None );
+/// ```
+#[ derive( Debug, Default, Clone, PartialEq, Eq ) ]
+pub struct RatchetSelector< T >
+{
+  floor : Option< T >,
+}
+
+impl< T : Ord + Copy > RatchetSelector< T >
+{
+  /// Creates selector that has selected nothing yet.
+  pub fn new() -> Self
+  {
+    Self { floor : None }
+  }
+
+  /// Returns the highest value selected so far.
+  pub fn floor( &self ) -> Option< T >
+  {
+    self.floor
+  }
+
+  /// Selects a single value not below the highest value selected so far.
+  ///
+  /// Takes the first value `attempt` returns for the inputs. If it is below the floor, takes
+  /// the smallest available value that is allowed and not below the floor instead. Returns
+  /// `None` if there is no such value or `attempt` returns no values.
+  pub fn select
+  (
+    &mut self,
+    available : &[ T ],
+    allowed : &[ ValueT< T > ],
+    preferred : &[ ValueT< T > ]
+  ) -> Option< T >
+  {
+    let available = sorted( available );
+    let target = *attempt_sorted_unchecked( &available, allowed, preferred ).first()?;
+    let selected = match self.floor
+    {
+      Some( floor ) if target < floor =>
+      {
+        let ladder = reduce_by_allowed( &available, allowed );
+        *ladder.get( ladder.partition_point( | num | *num < floor ) )?
+      }
+      _ => target,
+    };
+    self.floor = Some( selected );
+    Some( selected )
+  }
+}
+
+/// Finds the smallest `allowed` vector for which `attempt` selects exactly `desired` values.
+///
+/// Every desired value has to be allowed, so the allow-list of `Value::Number` entries of
+/// desired values is the smallest candidate. Any other allowed value either gets selected
+/// itself or changes nothing, so the desired selection is reachable only if this candidate
+/// yields it, in the same order. Returns the candidate if it does, or `None` otherwise.
+///
+/// The only exception are preferences of `Value::Index`, which are resolved by position, so for
+/// them a larger allow-list might reach a selection reported as unreachable.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720, 1080 ];
+/// let preferred = [ Value::Number( 300 ), Value::Number( 1080 ) ];
+/// assert_eq!
+/// (
+///   minimal_allowed_for( &available, &preferred, &[ 720 ] ),
+///   Some( vec![ Value::Number( 720 ) ] )
+/// );
+/// assert_eq!( minimal_allowed_for( &available, &preferred, &[ 1080, 360 ] ), None );
+/// ```
+pub fn minimal_allowed_for< T : Ord + Copy >
+(
+  available : &[ T ],
+  preferred : &[ ValueT< T > ],
+  desired : &[ T ]
+) -> Option< Vec< ValueT< T > > >
+{
+  let mut values = desired.to_vec();
+  values.sort();
+  values.dedup();
+  let allowed : Vec< _ > = values.into_iter().map( ValueT::Number ).collect();
+  ( attempt( available, &allowed, preferred ) == desired ).then_some( allowed )
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, pairing every
+/// value with the number of ladder steps it is away from its preference.
+///
+/// The ladder consists of available values that are allowed. Steps are zero for values that
+/// satisfy the preference, like exact matches or values inside of a preferred range. Otherwise
+/// they count rungs from the gap where the preference would fit to the selected value, positive
+/// upwards and negative downwards, so the closest value above is one step away and the closest
+/// value below is minus one step away. For preferred index steps are the difference between the
+/// selected position and the preferred one.
+///
+/// Returns values in the same order as `attempt`, each with steps of the first preference that
+/// selects it.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720, 1080 ];
+/// let preferred = [ Value::Number( 360 ), Value::Number( 2160 ), Value::Index( 5 ) ];
+/// assert_eq!
+/// (
+///   attempt_with_steps( &available, &[ Value::AtMost( 720 ) ], &preferred ),
+///   vec![ ( 360, 0 ), ( 720, -1 ) ]
+/// );
+/// ```
+pub fn attempt_with_steps< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Vec< ( T, isize ) >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  describe_selection( &ladder, preferred, | value, index |
+  {
+    let index = index as isize;
+    match ( value, value.key() )
+    {
+      ( ValueT::Index( position ), _ ) => index - *position as isize,
+      _ if value.allows( &ladder, index as usize ) => 0,
+      ( _, Some( key ) ) =>
+      {
+        let gap = ladder.partition_point( | other | *other < key ) as isize;
+        if index >= gap { index - gap + 1 } else { index - gap }
+      }
+      ( _, None ) => 0,
+    }
+  })
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, treating
+/// preferences outside of the ladder according to `policy`.
+///
+/// The ladder consists of available values that are allowed. Preference is outside of the
+/// ladder if every value of the ladder is above it or every value is below it, or if its index
+/// is out of bounds. `OverflowPolicy::ClampToBound` works like `attempt`,
+/// `OverflowPolicy::Drop` skips such preferences, and `OverflowPolicy::Error` returns
+/// `AttemptError::OutOfRange` if there is any. Preferences are never outside of an empty ladder.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 360, 720 ];
+/// let preferred = [ Value::Number( 240 ), Value::Number( 720 ) ];
+/// let select = | policy | attempt_with_overflow( &available, &[ Value::Any ], &preferred, policy );
+/// assert_eq!( select( OverflowPolicy::ClampToBound ), Ok( vec![ 360, 720 ] ) );
+/// assert_eq!( select( OverflowPolicy::Drop ), Ok( vec![ 720 ] ) );
+/// assert_eq!( select( OverflowPolicy::Error ), Err( AttemptError::OutOfRange ) );
+/// ```
+pub fn attempt_with_overflow< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  policy : OverflowPolicy
+) -> Result< Vec< T >, AttemptError >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  let ( Some( &min ), Some( &max ) ) = ( ladder.first(), ladder.last() ) else
+  {
+    return Ok( vec![] );
+  };
+  let is_outside = | value : &ValueT< T > | match value
+  {
+    ValueT::Any => false,
+    ValueT::Number( num ) => *num < min || max < *num,
+    ValueT::Range( start, end ) =>
+    {
+      let ( start, end ) = normalize_range( *start, *end );
+      end < min || max < start
+    }
+    ValueT::AtLeast( threshold ) => max < *threshold,
+    ValueT::AtMost( threshold ) => *threshold < min,
+    ValueT::Index( index ) => *index >= ladder.len(),
+  };
+  let preferred : Vec< _ > = match policy
+  {
+    OverflowPolicy::ClampToBound => preferred.to_vec(),
+    OverflowPolicy::Drop => preferred.iter().copied().filter( | value | !is_outside( value ) ).collect(),
+    OverflowPolicy::Error if preferred.iter().any( is_outside ) => return Err( AttemptError::OutOfRange ),
+    OverflowPolicy::Error => preferred.to_vec(),
+  };
+  Ok( reduce_by_preferred( &ladder, &preferred ) )
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, considering
+/// only available values inside of the `window`.
+///
+/// Works like `attempt_sorted_unchecked` on the part of `available` inside of the `window`,
+/// which is borrowed rather than copied.
+///
+/// Assumes `available` is sorted in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720, 1080 ];
+/// assert_eq!( attempt_windowed( &available, &[ Value::Any ], &[ Value::Number( 1080 ) ], 300..=800 ), vec![ 720 ] );
+/// ```
+pub fn attempt_windowed< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  window : RangeInclusive< T >
+) -> Vec< T >
+{
+  debug_assert!( available.is_sorted(), "`available` must be sorted in ascending order" );
+  let lower = available.partition_point( | num | num < window.start() );
+  let upper = available.partition_point( | num | num <= window.end() ).max( lower );
+  attempt_sorted_unchecked( &available[ lower..upper ], allowed, preferred )
+}
+
+/// Groups values `attempt` selects into runs of adjacent ladder values, along with positions
+/// of `preferred` values that select them.
+///
+/// The ladder consists of available values that are allowed. Every run is described by its
+/// smallest value, its largest value and positions of preferences selecting any of its values,
+/// as `preferences_for` finds them. Returns runs in ascending order, positions in every run in
+/// ascending order too.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 480, 720, 1080 ];
+/// let preferred = [ Value::Number( 360 ), Value::Number( 1080 ), Value::Number( 400 ) ];
+/// assert_eq!
+/// (
+///   coverage_intervals( &available, &[ Value::Any ], &preferred ),
+///   vec![ ( 360, 480, vec![ 0, 2 ] ), ( 1080, 1080, vec![ 1 ] ) ]
+/// );
+/// ```
+pub fn coverage_intervals< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Vec< ( T, T, Vec< usize > ) >
+{
+  let mut ladder = reduce_by_allowed( &sorted( available ), allowed );
+  ladder.dedup();
+  let mut covered : Vec< Vec< usize > > = vec![ vec![]; ladder.len() ];
+  for ( position, value ) in preferred.iter().enumerate()
+  {
+    for num in reduce_by_preferred( &ladder, core::slice::from_ref( value ) )
+    {
+      covered[ ladder.partition_point( | other | *other < num ) ].push( position );
+    }
+  }
+
+  let mut intervals : Vec< ( T, T, Vec< usize > ) > = vec![];
+  let mut previous_covered = false;
+  for ( num, positions ) in ladder.into_iter().zip( covered )
+  {
+    if positions.is_empty()
+    {
+      previous_covered = false;
+      continue;
+    }
+    match intervals.last_mut()
+    {
+      Some( ( _, end, run ) ) if previous_covered =>
+      {
+        *end = num;
+        run.extend( positions );
+      }
+      _ => intervals.push( ( num, num, positions ) ),
+    }
+    previous_covered = true;
+  }
+  for ( _, _, positions ) in &mut intervals
+  {
+    positions.sort();
+    positions.dedup();
+  }
+  intervals
+}
+
+/// Matches preferred numbers exactly, reporting the gap they fall into instead of a fallback.
+///
+/// The ladder consists of available values that are allowed. Every `Value::Number` preference
+/// yields `Ok` with its value if the ladder has it, or `Err` with the closest ladder values below
+/// and above it otherwise. Other kinds of preferences do not match single values and are
+/// skipped. Returns results in order of `preferred`.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let preferred = [ Value::Number( 360 ), Value::Number( 480 ), Value::Number( 1080 ) ];
+/// assert_eq!
+/// (
+///   attempt_exact_or_gap( &available, &[ Value::Any ], &preferred ),
+///   vec![ Ok( 360 ), Err( ( Some( 360 ), Some( 720 ) ) ), Err( ( Some( 720 ), None ) ) ]
+/// );
+/// ```
+pub fn attempt_exact_or_gap< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Vec< Result< T, Gap< T > > >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  preferred.iter()
+  .filter_map( | value | match value
+  {
+    ValueT::Number( num ) => Some( *num ),
+    _ => None,
+  })
+  .map( | num |
+  {
+    let lower = ladder.partition_point( | other | *other < num );
+    let upper = ladder.partition_point( | other | *other <= num );
+    if lower < upper
+    {
+      Ok( num )
+    }
+    else
+    {
+      Err( ( lower.checked_sub( 1 ).map( | index | ladder[ index ] ), ladder.get( upper ).copied() ) )
+    }
+  })
+  .collect()
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, biased towards
+/// lower values.
+///
+/// When the preferred value lies between two allowed values, the distance to the value above
+/// is multiplied by `bias` before the closest one is picked, and lower value wins ties. With
+/// `bias` of one this is `TieBreak::PreferNearest`, greater `bias` makes lower values win more
+/// often, and `bias` below one favours higher values instead.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 360, 720 ];
+/// let preferred = [ Value::Number( 600 ) ];
+/// assert_eq!( attempt_efficient( &available, &[ Value::Any ], &preferred, 1.0 ), vec![ 720 ] );
+/// assert_eq!( attempt_efficient( &available, &[ Value::Any ], &preferred, 2.0 ), vec![ 360 ] );
+/// ```
+pub fn attempt_efficient< T : Distance >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  bias : f64
+) -> Vec< T >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  if preferred.contains( &ValueT::Any )
+  {
+    return ladder;
+  }
+  reduce_by_preferred_by
+  (
+    &ladder,
+    preferred,
+    | ( start, end ), below, above |
+    {
+      if ( ladder[ above ].distance( end ) as f64 ) * bias < ladder[ below ].distance( start ) as f64
+      {
+        above
+      }
+      else
+      {
+        below
+      }
+    }
+  )
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, counting
+/// allowed values left out.
+///
+/// Returns values `attempt` selects along with the number of distinct available values that
+/// are allowed but not selected.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720, 1080 ];
+/// assert_eq!
+/// (
+///   attempt_with_remaining( &available, &[ Value::AtMost( 720 ) ], &[ Value::Number( 360 ) ] ),
+///   ( vec![ 360 ], 2 )
+/// );
+/// ```
+pub fn attempt_with_remaining< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> ( Vec< T >, usize )
+{
+  let available = sorted( available );
+  let mut ladder = reduce_by_allowed( &available, allowed );
+  ladder.dedup();
+  let selected = attempt_sorted_unchecked( &available, allowed, preferred );
+  let chosen : BTreeSet< _ > = selected.iter().collect();
+  let remaining = ladder.iter().filter( | num | !chosen.contains( num ) ).count();
+  ( selected, remaining )
+}
+
+/// Checks whether `attempt` selects every value of `target` for the inputs.
+///
+/// Unlike checking that the selection is not empty, verifies the expected outcome. The check
+/// passes if the selection has other values besides `target` too.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let preferred = [ Value::Number( 300 ), Value::Number( 720 ) ];
+/// assert!( can_select( &available, &[ Value::Any ], &preferred, &[ 720, 360 ] ) );
+/// assert!( !can_select( &available, &[ Value::Any ], &preferred, &[ 240 ] ) );
+/// ```
+pub fn can_select< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  target : &[ T ]
+) -> bool
+{
+  let selected : BTreeSet< _ > = attempt( available, allowed, preferred ).into_iter().collect();
+  target.iter().all( | num | selected.contains( num ) )
+}
+
+/// Reduces original array by `preferred` vector and its size, breaking ties by `priority`.
+///
+/// Works like `reduce_by_preferred_with` with `TieBreak::PreferNearest`, but when the values
+/// below and above of the preferred value are equally close, the one of greater priority is
+/// returned, and the value above if priorities are equal too. `priority[ i ]` is the priority
+/// of `original[ i ]`.
+///
+/// Returns copy of `original` if `preferred` contains `Value::Any`.
+///
+/// Assumes `original` is sorted in ascending order.
+///
+/// # Panics
+///
+/// Panics if `priority` and `original` have different lengths.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let original = [ 360, 720 ];
+/// let preferred = [ Value::Number( 540 ) ];
+/// assert_eq!( reduce_by_preferred_keyed( &original, &preferred, &[ 1, 0 ] ), vec![ 360 ] );
+/// assert_eq!( reduce_by_preferred_keyed( &original, &preferred, &[ 0, 1 ] ), vec![ 720 ] );
+/// ```
+pub fn reduce_by_preferred_keyed< T : Distance, P : Ord >
+(
+  original : &[ T ],
+  preferred : &[ ValueT< T > ],
+  priority : &[ P ]
+) -> Vec< T >
+{
+  assert_eq!( original.len(), priority.len(), "`priority` must have a value for every value of `original`" );
+  if preferred.contains( &ValueT::Any )
+  {
+    return original.to_vec();
+  }
+  reduce_by_preferred_by
+  (
+    original,
+    preferred,
+    | ( start, end ), below, above |
+    {
+      match original[ above ].distance( end ).cmp( &original[ below ].distance( start ) )
+      {
+        core::cmp::Ordering::Less => above,
+        core::cmp::Ordering::Greater => below,
+        core::cmp::Ordering::Equal if priority[ below ] > priority[ above ] => below,
+        core::cmp::Ordering::Equal => above,
+      }
+    }
+  )
+}
+
+/// Reduces `avaliable` vector by `allowed` vector filter and every set of preferences, packing
+/// the results into a single buffer.
+///
+/// Returns values `attempt` selects for every set of `preference_sets` one after another, along
+/// with offsets delimiting them. Offsets start with zero and have one more item than the sets,
+/// so values of set `i` are `values[ offsets[ i ]..offsets[ i + 1 ] ]`. Sorts `available` once
+/// and allocates no result vector per set.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let sets = [ vec![ Value::Number( 360 ) ], vec![], vec![ Value::Number( 240 ), Value::Number( 720 ) ] ];
+/// let ( values, offsets ) = attempt_batch_packed( &[ 240, 360, 720 ], &[ Value::Any ], &sets );
+/// assert_eq!( values, vec![ 360, 240, 720 ] );
+/// assert_eq!( offsets, vec![ 0, 1, 1, 3 ] );
+/// ```
+pub fn attempt_batch_packed< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preference_sets : &[ Vec< ValueT< T > > ]
+) -> ( Vec< T >, Vec< usize > )
+{
+  let available = sorted( available );
+  let mut values = vec![];
+  let mut offsets = Vec::with_capacity( preference_sets.len() + 1 );
+  offsets.push( 0 );
+  for preferred in preference_sets
+  {
+    values.extend( attempt_iter( &available, allowed, preferred ) );
+    offsets.push( values.len() );
+  }
+  ( values, offsets )
+}
+
+/// Measures similarity of two selections as the Jaccard index of their values.
+///
+/// Treats both selections as sets, ignoring order and duplicates, and returns the size of their
+/// intersection divided by the size of their union. Returns `1.0` if both selections are empty.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// assert_eq!( jaccard( &[ 360, 720 ], &[ 720, 1080, 360, 240 ] ), 0.5 );
+/// assert_eq!( jaccard::< i32 >( &[], &[] ), 1.0 );
+/// ```
+pub fn jaccard< T : Ord >( a : &[ T ], b : &[ T ] ) -> f64
+{
+  let a : BTreeSet< _ > = a.iter().collect();
+  let b : BTreeSet< _ > = b.iter().collect();
+  let union = a.union( &b ).count();
+  if union == 0
+  {
+    return 1.0;
+  }
+  a.intersection( &b ).count() as f64 / union as f64
+}
+
+/// Describes how both `allowed` and `preferred` vector filters treat every available value.
+///
+/// Returns a description for every value of `available` in ascending order. Positions of
+/// preferences selecting a value are found like with `preferences_for`.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let info = annotate( &[ 360, 240 ], &[ Value::AtLeast( 300 ) ], &[ Value::Number( 240 ) ] );
+/// assert_eq!
+/// (
+///   info,
+///   vec!
+///   [
+///     RungInfo { value : 240, allowed : false, preferred_by : vec![], selected : false },
+///     RungInfo { value : 360, allowed : true, preferred_by : vec![ 0 ], selected : true },
+///   ]
+/// );
+/// ```
+pub fn annotate< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Vec< RungInfo< T > >
+{
+  let available = sorted( available );
+  let ladder = reduce_by_allowed( &available, allowed );
+  let selected : BTreeSet< _ > = attempt_sorted_unchecked( &available, allowed, preferred ).into_iter().collect();
+  let mut preferred_by : BTreeMap< T, Vec< usize > > = BTreeMap::new();
+  for ( position, value ) in preferred.iter().enumerate()
+  {
+    for num in reduce_by_preferred( &ladder, core::slice::from_ref( value ) )
+    {
+      preferred_by.entry( num ).or_default().push( position );
+    }
+  }
+  available.iter()
+  .map( | num | RungInfo
+  {
+    value : *num,
+    allowed : ladder.binary_search( num ).is_ok(),
+    preferred_by : preferred_by.get( num ).cloned().unwrap_or_default(),
+    selected : selected.contains( num ),
+  })
+  .collect()
+}
+
+/// Reduces `avaliable` vector by values the `spec` allows and `preferred` vector filter.
+///
+/// Works like `attempt` with `allowed` vector listing every value of the `spec`, without
+/// enumerating them.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let spec = AllowedSpec { min : 240, max : 960, step : 240 };
+/// assert_eq!( attempt_spec( &[ 240, 360, 480, 720, 1080 ], &spec, &[ Value::Number( 360 ) ] ), vec![ 480 ] );
+/// ```
+pub fn attempt_spec< T : Distance >
+(
+  available : &[ T ],
+  spec : &AllowedSpec< T >,
+  preferred : &[ ValueT< T > ]
+) -> Vec< T >
+{
+  let mut ladder : Vec< _ > = sorted( available ).iter().copied().filter( | num | spec.allows( *num ) ).collect();
+  ladder.dedup();
+  attempt_sorted_unchecked( &ladder, &[ ValueT::Any ], preferred )
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, selecting a
+/// single value for every cluster of preferences.
+///
+/// Preferred numbers are sorted and split into clusters wherever two adjacent ones are more
+/// than `cluster_gap` apart, so every number is within `cluster_gap` of its neighbour in the
+/// cluster. The centroid of a cluster is the mean of its numbers, and the cluster selects the
+/// allowed value nearest to the centroid, as `quantize` finds it. Other kinds of preferences do
+/// not have a single value and are skipped.
+///
+/// Returns values in order of the first preference of every cluster, without duplicates.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720, 1080 ];
+/// let preferred = [ Value::Number( 700 ), Value::Number( 200 ), Value::Number( 800 ), Value::Number( 260 ) ];
+/// assert_eq!( attempt_clustered( &available, &[ Value::Any ], &preferred, 150 ), vec![ 720, 240 ] );
+/// ```
+pub fn attempt_clustered
+(
+  available : &[ i32 ],
+  allowed : &[ Value ],
+  preferred : &[ Value ],
+  cluster_gap : u32
+) -> Vec< i32 >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  let mut numbers : Vec< ( i32, usize ) > = preferred.iter()
+  .enumerate()
+  .filter_map( | ( position, value ) | match value
+  {
+    Value::Number( num ) => Some( ( *num, position ) ),
+    _ => None,
+  })
+  .collect();
+  numbers.sort();
+
+  let mut clusters : Vec< ( usize, f64 ) > = vec![];
+  for cluster in numbers.chunk_by( | a, b | a.0.abs_diff( b.0 ) <= cluster_gap )
+  {
+    let first = cluster.iter().map( | ( _, position ) | *position ).min().unwrap_or_default();
+    let sum : i64 = cluster.iter().map( | ( num, _ ) | i64::from( *num ) ).sum();
+    clusters.push( ( first, sum as f64 / cluster.len() as f64 ) );
+  }
+  clusters.sort_by_key( | ( first, _ ) | *first );
+
+  let mut seen = BTreeSet::new();
+  clusters.into_iter()
+  .filter_map( | ( _, centroid ) | quantize( &ladder, centroid ) )
+  .filter( | num | seen.insert( *num ) )
+  .collect()
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, pairing every
+/// value with its distance from the preference and ordering the cheapest first.
+///
+/// Values selected by `Value::Number` preferences cost their distance from the preferred
+/// number, as with `selection_cost`, and other values cost nothing. Every value takes the cost
+/// of the first preference that selects it. Returns values `attempt` selects sorted by ascending
+/// cost, values of equal cost in the same order as `attempt`.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let preferred = [ Value::Number( 700 ), Value::Number( 360 ) ];
+/// assert_eq!( attempt_by_cost( &available, &[ Value::Any ], &preferred ), vec![ ( 360, 0 ), ( 720, 20 ) ] );
+/// ```
+pub fn attempt_by_cost< T : Distance >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Vec< ( T, u64 ) >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  let mut vec = describe_selection( &ladder, preferred, | value, index | match value
+  {
+    ValueT::Number( target ) => ladder[ index ].distance( *target ),
+    _ => 0,
+  });
+  vec.sort_by_key( | ( _, cost ) | *cost );
+  vec
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, returning every
+/// allowed value if no value is selected.
+///
+/// Works like `attempt`, but if it selects nothing while some available values are allowed,
+/// returns all of them in ascending order instead. A selection that is not empty is returned
+/// as is and never extended. Since every preference resolves to some allowed value, this
+/// happens when `preferred` is empty.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// assert_eq!( attempt_or_all( &available, &[ Value::AtMost( 360 ) ], &[] ), vec![ 240, 360 ] );
+/// assert_eq!( attempt_or_all( &available, &[ Value::AtMost( 360 ) ], &[ Value::Number( 360 ) ] ), vec![ 360 ] );
+/// ```
+pub fn attempt_or_all< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Vec< T >
+{
+  let available = sorted( available );
+  let selected = attempt_sorted_unchecked( &available, allowed, preferred );
+  if selected.is_empty()
+  {
+    return reduce_by_allowed( &available, allowed );
+  }
+  selected
+}
+
+/// Selects `count` values with repetition, approximating the distribution of `targets`.
+///
+/// Every target value resolves to an allowed value like `Value::Number` preference does with
+/// `attempt`, and weights of targets resolving to the same value add up. Weights that are not
+/// positive are ignored. Every value gets its share of `count` proportional to its weight,
+/// rounded down, and values with the largest remainders get one more until `count` values are
+/// selected, earlier target first on equal remainders.
+///
+/// Returns copies of every value next to each other, in order of the first target resolving
+/// to it. Returns nothing if no available value is allowed or no weight is positive.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 480, 720 ];
+/// let targets = [ ( 720, 0.5 ), ( 480, 0.3 ), ( 240, 0.2 ) ];
+/// assert_eq!
+/// (
+///   attempt_distribution( &available, &[ Value::Any ], &targets, 6 ),
+///   vec![ 720, 720, 720, 480, 480, 240 ]
+/// );
+/// ```
+pub fn attempt_distribution< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  targets : &[ ( T, f64 ) ],
+  count : usize
+) -> Vec< T >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  let mut weights : Vec< ( T, f64 ) > = vec![];
+  for ( target, weight ) in targets
+  {
+    let Some( index ) = resolve_number( &ladder, *target ) else { break };
+    if weight.is_nan() || *weight <= 0.0
+    {
+      continue;
+    }
+    match weights.iter_mut().find( | ( num, _ ) | *num == ladder[ index ] )
+    {
+      Some( ( _, total ) ) => *total += weight,
+      None => weights.push( ( ladder[ index ], *weight ) ),
+    }
+  }
+  let total : f64 = weights.iter().map( | ( _, weight ) | weight ).sum();
+  if weights.is_empty() || !total.is_finite()
+  {
+    return vec![];
+  }
+
+  let quotas : Vec< f64 > = weights.iter().map( | ( _, weight ) | count as f64 * weight / total ).collect();
+  let mut shares : Vec< usize > = quotas.iter().map( | quota | *quota as usize ).collect();
+  let mut order : Vec< usize > = ( 0..quotas.len() ).collect();
+  order.sort_by( | a, b | ( quotas[ *b ] - shares[ *b ] as f64 ).total_cmp( &( quotas[ *a ] - shares[ *a ] as f64 ) ) );
+  let assigned : usize = shares.iter().sum();
+  for index in order.into_iter().cycle().take( count.saturating_sub( assigned ) )
+  {
+    shares[ index ] += 1;
+  }
+  weights.iter()
+  .zip( shares )
+  .flat_map( | ( ( num, _ ), share ) | core::iter::repeat_n( *num, share ) )
+  .collect()
+}
+
+/// Selects a single value for a preferred number that changes often, like a slider position.
+///
+/// Computes the ladder of available values that are allowed once, and remembers the last
+/// bounds searched for, so updates that stay between the same two values skip the search. The
+/// bounds are kept in a `Cell`, so the selector is not `Sync`, clone it to use on other threads.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let selector = SliderSelector::new( &[ 240, 360, 720 ], &[ Value::AtMost( 720 ) ] );
+/// assert_eq!( selector.on_value( 250 ), Some( 240 ) );
+/// assert_eq!( selector.on_value( 300 ), Some( 360 ) );
+/// assert_eq!( selector.on_value( 350 ), Some( 360 ) );
+/// assert_eq!( selector.on_value( 1080 ), Some( 720 ) );
+/// ```
+#[ derive( Debug, Clone ) ]
+pub struct SliderSelector< T >
+{
+  ladder : Vec< T >,
+  hint : core::cell::Cell< usize >,
+}
+
+impl< T : Distance > SliderSelector< T >
+{
+  /// Creates selector choosing among `available` values that `allowed` vector allows.
+  pub fn new( available : &[ T ], allowed : &[ ValueT< T > ] ) -> Self
+  {
+    let ladder = reduce_by_allowed( &sorted( available ), allowed );
+    Self { ladder, hint : core::cell::Cell::new( 0 ) }
+  }
+
+  /// Selects the allowed value nearest to the preferred number, the greater one if two are
+  /// equally near.
+  ///
+  /// Returns `None` if no available value is allowed.
+  pub fn on_value( &self, preferred : T ) -> Option< T >
+  {
+    let ladder = &self.ladder;
+    let hint = self.hint.get();
+    let is_hit = hint <= ladder.len()
+      && ( hint == 0 || ladder[ hint - 1 ] < preferred )
+      && ( hint == ladder.len() || preferred <= ladder[ hint ] );
+    let upper = if is_hit { hint } else { ladder.partition_point( | num | *num < preferred ) };
+    self.hint.set( upper );
+    match ( upper.checked_sub( 1 ).map( | below | ladder[ below ] ), ladder.get( upper ) )
+    {
+      ( Some( below ), Some( above ) ) if below.distance( preferred ) < above.distance( preferred ) => Some( below ),
+      ( _, Some( above ) ) => Some( *above ),
+      ( below, None ) => below,
+    }
+  }
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, pairing every
+/// value with the way it was derived from its preference.
+///
+/// Rules of `attempt` round up between values, so `MatchKind::RoundedDown` is not returned.
+/// Returns values in the same order as `attempt`, each with the kind of match for the first
+/// preference that selects it.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let preferred = [ Value::Number( 300 ), Value::Number( 1080 ), Value::Number( 100 ), Value::Number( 360 ) ];
+/// assert_eq!
+/// (
+///   attempt_classified_each( &available, &[ Value::Any ], &preferred ),
+///   vec![ ( 360, MatchKind::RoundedUp ), ( 720, MatchKind::ClampedHigh ), ( 240, MatchKind::ClampedLow ) ]
+/// );
+/// ```
+pub fn attempt_classified_each< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Vec< ( T, MatchKind ) >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  describe_selection( &ladder, preferred, | value, index | value.classify( &ladder, index ) )
+}
+
+/// Converts preferred range from `lo` to `hi` into preferred numbers of values it covers.
+///
+/// Returns `Value::Number` for every distinct available value inside of the range that is
+/// allowed, in ascending order. Inverted range is treated as normalized.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// assert_eq!
+/// (
+///   expand_range_preference( &[ 240, 360, 480, 720 ], &[ Value::AtLeast( 300 ) ], 200, 500 ),
+///   vec![ Value::Number( 360 ), Value::Number( 480 ) ]
+/// );
+/// ```
+pub fn expand_range_preference< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  lo : T,
+  hi : T
+) -> Vec< ValueT< T > >
+{
+  let mut ladder = reduce_by_allowed( &sorted( available ), allowed );
+  ladder.dedup();
+  reduce_by_allowed( &ladder, &[ ValueT::Range( lo, hi ) ] ).into_iter().map( ValueT::Number ).collect()
+}
+
+/// Reports for every preferred number the value it selects or why it could not be satisfied.
+///
+/// The ladder consists of available values that are allowed. Preferred number that is available
+/// but not allowed, or any preferred number if the ladder is empty, is unmet with
+/// `UnmetReason::NotAllowed`. Preferred number below or above every value of the ladder is
+/// unmet with `UnmetReason::BelowLadder` or `UnmetReason::AboveLadder`. Otherwise it matches the
+/// value `attempt` selects for it, exactly or not. Other kinds of preferences do not have a
+/// single value and are skipped. Returns outcomes in order of `preferred`.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720, 1080 ];
+/// let preferred = [ Value::Number( 480 ), Value::Number( 1080 ), Value::Number( 100 ) ];
+/// assert_eq!
+/// (
+///   attempt_report( &available, &[ Value::Range( 240, 720 ) ], &preferred ),
+///   vec!
+///   [
+///     PrefOutcome::Matched { pref : 480, chosen : 720, exact : false },
+///     PrefOutcome::Unmet { pref : 1080, reason : UnmetReason::NotAllowed },
+///     PrefOutcome::Unmet { pref : 100, reason : UnmetReason::BelowLadder },
+///   ]
+/// );
+/// ```
+pub fn attempt_report< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Vec< PrefOutcome< T > >
+{
+  let available = sorted( available );
+  let ladder = reduce_by_allowed( &available, allowed );
+  preferred.iter()
+  .filter_map( | value | match value
+  {
+    ValueT::Number( num ) => Some( *num ),
+    _ => None,
+  })
+  .map( | pref |
+  {
+    let is_allowed = ladder.binary_search( &pref ).is_ok();
+    let reason = match ( ladder.first(), ladder.last() )
+    {
+      _ if !is_allowed && available.binary_search( &pref ).is_ok() => UnmetReason::NotAllowed,
+      ( Some( min ), _ ) if pref < *min => UnmetReason::BelowLadder,
+      ( _, Some( max ) ) if *max < pref => UnmetReason::AboveLadder,
+      ( Some( _ ), Some( _ ) ) =>
+      {
+        let chosen = ladder[ ladder.partition_point( | num | *num < pref ) ];
+        return PrefOutcome::Matched { pref, chosen, exact : is_allowed };
+      }
+      _ => UnmetReason::NotAllowed,
+    };
+    PrefOutcome::Unmet { pref, reason }
+  })
+  .collect()
+}
+
+/// Returns up to `n` distinct values of `original` closest to the `target`, closest first.
+///
+/// Of two values equally close to the `target`, the greater one comes first.
+///
+/// Assumes `original` is sorted in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// assert_eq!( closest_n( &[ 240, 360, 480, 720 ], 420, 3 ), vec![ 480, 360, 240 ] );
+/// ```
+pub fn closest_n< T : Distance >( original : &[ T ], target : T, n : usize ) -> Vec< T >
+{
+  debug_assert!( original.is_sorted(), "`original` must be sorted in ascending order" );
+  let mut values = original.to_vec();
+  values.dedup();
+  let mut upper = values.partition_point( | num | *num < target );
+  let mut lower = upper;
+  let mut vec = Vec::with_capacity( n.min( values.len() ) );
+  while vec.len() < n && ( lower > 0 || upper < values.len() )
+  {
+    let take_upper = match ( lower.checked_sub( 1 ), values.get( upper ) )
+    {
+      ( Some( below ), Some( above ) ) => above.distance( target ) <= values[ below ].distance( target ),
+      ( _, above ) => above.is_some(),
+    };
+    if take_upper
+    {
+      vec.push( values[ upper ] );
+      upper += 1;
+    }
+    else
+    {
+      lower -= 1;
+      vec.push( values[ lower ] );
+    }
+  }
+  vec
+}
+
+/// Reduces original array by `preferred` vector, selecting the `k`-th closest value for every
+/// preferred number.
+///
+/// Values are ordered by distance as with `closest_n`, and `k` of one selects the closest
+/// value. Preferences with fewer than `k` values of `original` select nothing. Other kinds of
+/// preferences do not have a single value and are skipped. Returns values in order of
+/// `preferred` without duplicates.
+///
+/// Assumes `original` is sorted in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let original = [ 240, 360, 720 ];
+/// assert_eq!( reduce_by_kth_nearest( &original, &[ Value::Number( 360 ), Value::Number( 700 ) ], 2 ), vec![ 240, 360 ] );
+/// ```
+pub fn reduce_by_kth_nearest< T : Distance >( original : &[ T ], preferred : &[ ValueT< T > ], k : usize ) -> Vec< T >
+{
+  let Some( skip ) = k.checked_sub( 1 ) else
+  {
+    return vec![];
+  };
+  let mut seen = BTreeSet::new();
+  preferred.iter()
+  .filter_map( | value | match value
+  {
+    ValueT::Number( num ) => closest_n( original, *num, k ).get( skip ).copied(),
+    _ => None,
+  })
+  .filter( | num | seen.insert( *num ) )
+  .collect()
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, recording every
+/// decision into the `log`.
+///
+/// Works like `attempt`, and appends to the `log` a check of every distinct available value
+/// against `allowed` vector, in ascending order, followed by the values preferences select and
+/// collapse, in order of `preferred`. If `preferred` contains `Value::Any`, every distinct
+/// allowed value is recorded once as selected by the first such preference, even though
+/// duplicates of available values are selected like in `attempt`. `attempt` itself records
+/// nothing and is not slowed down.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let mut log = AuditLog::new();
+/// let selected = attempt_audited( &[ 240, 720 ], &[ Value::AtLeast( 300 ) ], &[ Value::Number( 360 ) ], &mut log );
+/// assert_eq!( selected, vec![ 720 ] );
+/// assert_eq!
+/// (
+///   log.events(),
+///   &[
+///     AuditEvent::Allowed { value : 240, allowed : false },
+///     AuditEvent::Allowed { value : 720, allowed : true },
+///     AuditEvent::Selected { preference : 0, value : 720, kind : MatchKind::ClampedLow },
+///   ]
+/// );
+/// ```
+pub fn attempt_audited< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  log : &mut AuditLog< T >
+) -> Vec< T >
+{
+  let available = sorted( available );
+  let ladder = reduce_by_allowed( &available, allowed );
+  // `Value::Any` in `allowed` keeps duplicates, which are logged once
+  let mut distinct_ladder = ladder.clone();
+  distinct_ladder.dedup();
+  let mut distinct = available.to_vec();
+  distinct.dedup();
+  log.events.extend( distinct.into_iter().map( | value | AuditEvent::Allowed
+  {
+    value,
+    allowed : distinct_ladder.binary_search( &value ).is_ok(),
+  }));
+
+  if let Some( preference ) = preferred.iter().position( | value | *value == ValueT::Any )
+  {
+    log.events.extend( distinct_ladder.iter().map( | value | AuditEvent::Selected
+    {
+      preference,
+      value : *value,
+      kind : MatchKind::Exact,
+    }));
+    return ladder;
+  }
+  let mut seen = BTreeSet::new();
+  let mut vec = vec![];
+  for ( preference, value ) in preferred.iter().enumerate()
+  {
+    for num in reduce_by_preferred( &ladder, core::slice::from_ref( value ) )
+    {
+      if !seen.insert( num )
+      {
+        log.events.push( AuditEvent::Collapsed { preference, value : num } );
+        continue;
+      }
+      let kind = value.classify( &ladder, ladder.partition_point( | other | *other < num ) );
+      log.events.push( AuditEvent::Selected { preference, value : num, kind } );
+      vec.push( num );
+    }
+  }
+  vec
+}
+
+/// Named set of preferences that can be applied to many ladders.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let mobile = PreferenceProfile::new( "mobile", vec![ Value::AtMost( 720 ) ] );
+/// assert_eq!( mobile.apply( &[ 360, 720, 1080 ], &[ Value::Any ] ), vec![ 720 ] );
+/// assert_eq!( mobile.apply( &[ 1080, 2160 ], &[ Value::Any ] ), vec![ 1080 ] );
+/// ```
+#[ derive( Debug, Clone, PartialEq, Eq ) ]
+pub struct PreferenceProfile< T >
+{
+  /// Name the profile is looked up by.
+  pub name : alloc::string::String,
+  /// Preferred values of the profile.
+  pub preferred : Vec< ValueT< T > >,
+}
+
+impl< T : Ord + Copy > PreferenceProfile< T >
+{
+  /// Creates profile with the `name` and `preferred` values.
+  pub fn new( name : impl Into< alloc::string::String >, preferred : Vec< ValueT< T > > ) -> Self
+  {
+    Self { name : name.into(), preferred }
+  }
+
+  /// Reduces `avaliable` vector by `allowed` vector filter and preferences of the profile, like
+  /// `attempt`.
+  pub fn apply( &self, available : &[ T ], allowed : &[ ValueT< T > ] ) -> Vec< T >
+  {
+    attempt( available, allowed, &self.preferred )
+  }
+}
+
+/// Stores preference profiles by their names.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let mut registry = ProfileRegistry::new();
+/// registry.insert( PreferenceProfile::new( "desktop", vec![ Value::AtLeast( 1080 ) ] ) );
+/// let desktop = registry.get( "desktop" ).unwrap();
+/// assert_eq!( desktop.apply( &[ 720, 1080, 2160 ], &[ Value::Any ] ), vec![ 1080 ] );
+/// assert!( registry.get( "mobile" ).is_none() );
+/// ```
+#[ derive( Debug, Default, Clone, PartialEq, Eq ) ]
+pub struct ProfileRegistry< T >
+{
+  profiles : BTreeMap< alloc::string::String, PreferenceProfile< T > >,
+}
+
+impl< T > ProfileRegistry< T >
+{
+  /// Creates empty registry.
+  pub fn new() -> Self
+  {
+    Self { profiles : BTreeMap::new() }
+  }
+
+  /// Stores the `profile`, returning the profile it replaces under the same name.
+  pub fn insert( &mut self, profile : PreferenceProfile< T > ) -> Option< PreferenceProfile< T > >
+  {
+    self.profiles.insert( profile.name.clone(), profile )
+  }
+
+  /// Looks up the profile by its `name`.
+  pub fn get( &self, name : &str ) -> Option< &PreferenceProfile< T > >
+  {
+    self.profiles.get( name )
+  }
+
+  /// Removes the profile by its `name`, returning it.
+  pub fn remove( &mut self, name : &str ) -> Option< PreferenceProfile< T > >
+  {
+    self.profiles.remove( name )
+  }
+}
+
+/// Ladder of available values that are allowed, computed once for many selections.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let mut set = AllowedSet::new( &[ 240, 360, 720, 1080 ], &[ Value::AtMost( 720 ) ] );
+/// assert_eq!( set.select( &[ Value::Number( 480 ) ] ), vec![ 720 ] );
+/// set.insert( 480 );
+/// assert_eq!( set.select( &[ Value::Number( 480 ) ] ), vec![ 480 ] );
+/// ```
+#[ derive( Debug, Default, Clone, PartialEq, Eq ) ]
+pub struct AllowedSet< T >
+{
+  ladder : Vec< T >,
+}
+
+impl< T : Ord + Copy > AllowedSet< T >
+{
+  /// Creates set of `available` values that `allowed` vector allows.
+  pub fn new( available : &[ T ], allowed : &[ ValueT< T > ] ) -> Self
+  {
+    let mut ladder = reduce_by_allowed( &sorted( available ), allowed );
+    ladder.dedup();
+    Self { ladder }
+  }
+
+  /// Returns allowed values in ascending order.
+  pub fn ladder( &self ) -> &[ T ]
+  {
+    &self.ladder
+  }
+
+  /// Adds allowed value in place, keeping the ladder sorted.
+  ///
+  /// Returns whether the value was not in the set.
+  pub fn insert( &mut self, value : T ) -> bool
+  {
+    match self.ladder.binary_search( &value )
+    {
+      Ok( _ ) => false,
+      Err( index ) =>
+      {
+        self.ladder.insert( index, value );
+        true
+      }
+    }
+  }
+
+  /// Removes allowed value in place.
+  ///
+  /// Returns whether the value was in the set.
+  pub fn remove( &mut self, value : T ) -> bool
+  {
+    match self.ladder.binary_search( &value )
+    {
+      Ok( index ) =>
+      {
+        self.ladder.remove( index );
+        true
+      }
+      Err( _ ) => false,
+    }
+  }
+
+  /// Reduces the allowed values by `preferred` vector filter, like `attempt` does.
+  pub fn select( &self, preferred : &[ ValueT< T > ] ) -> Vec< T >
+  {
+    attempt_sorted_unchecked( &self.ladder, &[ ValueT::Any ], preferred )
+  }
+
+  /// Reduces the allowed values except the `exclude` ones by `preferred` vector filter, leaving
+  /// the set intact.
+  ///
+  /// Assumes `exclude` is sorted in ascending order.
+  pub fn select_excluding( &self, preferred : &[ ValueT< T > ], exclude : &[ T ] ) -> Vec< T >
+  {
+    debug_assert!( exclude.is_sorted(), "`exclude` must be sorted in ascending order" );
+    let ladder : Vec< _ > = self.ladder.iter()
+    .copied()
+    .filter( | num | exclude.binary_search( num ).is_err() )
+    .collect();
+    attempt_sorted_unchecked( &ladder, &[ ValueT::Any ], preferred )
+  }
+}
+
+/// Picks the allow-list among `candidates` that serves `preferred` vector best.
+///
+/// Candidates selecting any values beat ones selecting nothing, and among them the one of the
+/// lowest `selection_cost` wins, earlier candidate on equal costs. Returns position of the
+/// winning candidate along with the values `attempt` selects for it, or `None` if there are no
+/// candidates.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720, 1080 ];
+/// let candidates = [ vec![ Value::AtMost( 360 ) ], vec![ Value::AtLeast( 720 ) ] ];
+/// assert_eq!
+/// (
+///   best_allowed_policy( &available, &candidates, &[ Value::Number( 700 ) ] ),
+///   Some( ( 1, vec![ 720 ] ) )
+/// );
+/// ```
+pub fn best_allowed_policy< T : Distance >
+(
+  available : &[ T ],
+  candidates : &[ Vec< ValueT< T > > ],
+  preferred : &[ ValueT< T > ]
+) -> Option< ( usize, Vec< T > ) >
+{
+  let available = sorted( available );
+  candidates.iter()
+  .enumerate()
+  .map( | ( position, allowed ) |
+  {
+    let selected = attempt_sorted_unchecked( &available, allowed, preferred );
+    let cost = selection_cost( &available, allowed, preferred );
+    ( ( selected.is_empty(), cost ), position, selected )
+  })
+  .min_by_key( | ( rank, position, _ ) | ( *rank, *position ) )
+  .map( | ( _, position, selected ) | ( position, selected ) )
+}
+
+/// Measures how far preferred number `pref` is from selecting another value.
+///
+/// Returns the smallest change of `pref` after which the rules of `reduce_by_preferred` select
+/// a different allowed value for it, or `None` if no change does, because fewer than two
+/// distinct available values are allowed.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// assert_eq!( selection_sensitivity( &available, &[ Value::Any ], 350 ), Some( 11 ) );
+/// assert_eq!( selection_sensitivity( &available, &[ Value::Any ], 250 ), Some( 10 ) );
+/// assert_eq!( selection_sensitivity( &available, &[ Value::Number( 360 ) ], 250 ), None );
+/// ```
+pub fn selection_sensitivity< T : Distance >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  pref : T
+) -> Option< u64 >
+{
+  let mut ladder = reduce_by_allowed( &sorted( available ), allowed );
+  ladder.dedup();
+  let index = resolve_number( &ladder, pref )?;
+  let down = index.checked_sub( 1 ).map( | below | pref.distance( ladder[ below ] ) );
+  let up = ( index + 1 < ladder.len() ).then( || ladder[ index ].distance( pref ) + 1 );
+  down.into_iter().chain( up ).min()
+}
+
+/// Maps every preferred number to the value it selects.
+///
+/// The ladder consists of available values that are allowed, and every `Value::Number`
+/// preference is resolved against it following the rules of `reduce_by_preferred`. Duplicate
+/// preferred numbers are inserted again, the last one overwriting the earlier, though they
+/// always select the same value. Other kinds of preferences do not have a single value and are
+/// skipped. Returns empty map if no available value is allowed.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let map = attempt_map_prefs( &available, &[ Value::Any ], &[ Value::Number( 720 ), Value::Number( 300 ) ] );
+/// assert_eq!( map.get( &720 ), Some( &720 ) );
+/// assert_eq!( map.get( &300 ), Some( &360 ) );
+/// ```
+pub fn attempt_map_prefs< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> BTreeMap< T, T >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  preferred.iter()
+  .filter_map( | value | match value
+  {
+    ValueT::Number( num ) => resolve_number( &ladder, *num ).map( | index | ( *num, ladder[ index ] ) ),
+    _ => None,
+  })
+  .collect()
+}
+
+/// Selects a value for every preferred number arriving from the `prefs` stream.
+///
+/// Emits the value of `allowed_set` that `Value::Number` preference selects, following the
+/// rules of `reduce_by_preferred`. Emits nothing if `allowed_set` is empty.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+/// use futures::StreamExt;
+///
+/// let set = AllowedSet::new( &[ 240, 360, 720 ], &[ Value::Any ] );
+/// let prefs = futures::stream::iter( [ 300, 1080 ] );
+/// let selected : Vec< _ > = futures::executor::block_on( select_stream( set, prefs ).collect() );
+/// assert_eq!( selected, vec![ 360, 720 ] );
+/// ```
+#[ cfg( feature = "futures" ) ]
+pub fn select_stream< T, S >( allowed_set : AllowedSet< T >, prefs : S ) -> impl futures::Stream< Item = T >
+where
+  T : Ord + Copy,
+  S : futures::Stream< Item = T >,
+{
+  use futures::StreamExt;
+  prefs.filter_map( move | pref |
+  {
+    let ladder = allowed_set.ladder();
+    core::future::ready( resolve_number( ladder, pref ).map( | index | ladder[ index ] ) )
+  })
+}
+
+/// Finds the preferred number the ladder serves worst.
+///
+/// Every `Value::Number` preference is resolved against available values that are allowed,
+/// following the rules of `reduce_by_preferred`. Returns the preferred number farthest from the
+/// value it resolves to, along with the distance, the earlier one on equal distances. Returns
+/// `None` if every preferred number matches exactly or no available value is allowed.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let preferred = [ Value::Number( 300 ), Value::Number( 1080 ), Value::Number( 360 ) ];
+/// assert_eq!( worst_served( &available, &[ Value::Any ], &preferred ), Some( ( 1080, 360 ) ) );
+/// ```
+pub fn worst_served< T : Distance >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Option< ( T, u64 ) >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  preferred.iter()
+  .filter_map( | value | match value
+  {
+    ValueT::Number( num ) => resolve_number( &ladder, *num ).map( | index | ( *num, ladder[ index ].distance( *num ) ) ),
+    _ => None,
+  })
+  .filter( | ( _, distance ) | *distance > 0 )
+  .rev()
+  .max_by_key( | ( _, distance ) | *distance )
+}
+
+/// Reduces original array by `preferred` vector and its size, breaking ties towards `home`.
+///
+/// Works like `reduce_by_preferred_with` with `TieBreak::PreferNearest`, but when the values
+/// below and above of the preferred value are equally close, the one closer to `home` is
+/// returned, and the lower one if they are equally close to `home` too. Preferences below or
+/// above every value have a single closest value, which is returned regardless of `home`.
+///
+/// Returns copy of `original` if `preferred` contains `Value::Any`.
+///
+/// Assumes `original` is sorted in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let original = [ 360, 720 ];
+/// let preferred = [ Value::Number( 540 ) ];
+/// assert_eq!( reduce_by_preferred_home( &original, &preferred, 1080 ), vec![ 720 ] );
+/// assert_eq!( reduce_by_preferred_home( &original, &preferred, 240 ), vec![ 360 ] );
+/// assert_eq!( reduce_by_preferred_home( &original, &[ Value::Number( 2160 ) ], 240 ), vec![ 720 ] );
+/// ```
+pub fn reduce_by_preferred_home< T : Distance >( original : &[ T ], preferred : &[ ValueT< T > ], home : T ) -> Vec< T >
+{
+  if preferred.contains( &ValueT::Any )
+  {
+    return original.to_vec();
+  }
+  reduce_by_preferred_by
+  (
+    original,
+    preferred,
+    | ( start, end ), below, above |
+    {
+      let by_distance = original[ above ].distance( end ).cmp( &original[ below ].distance( start ) );
+      let by_home = original[ above ].distance( home ).cmp( &original[ below ].distance( home ) );
+      match by_distance.then( by_home )
+      {
+        core::cmp::Ordering::Less => above,
+        _ => below,
+      }
+    }
+  )
+}
+
+/// Describes how to turn the `old` selection into the `new` one.
+///
+/// Treats both selections as sets, ignoring order and duplicates. Returns an addition for
+/// every value of `new` missing from `old` and a removal for every value of `old` missing from
+/// `new`, ordered by ascending values.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// assert_eq!
+/// (
+///   selection_patch( &[ 720, 360 ], &[ 1080, 240, 720 ] ),
+///   vec![ Patch::Add( 240 ), Patch::Remove( 360 ), Patch::Add( 1080 ) ]
+/// );
+/// ```
+pub fn selection_patch< T : Ord + Copy >( old : &[ T ], new : &[ T ] ) -> Vec< Patch< T > >
+{
+  let old : BTreeSet< _ > = old.iter().copied().collect();
+  let new : BTreeSet< _ > = new.iter().copied().collect();
+  old.symmetric_difference( &new )
+  .map( | num | if new.contains( num ) { Patch::Add( *num ) } else { Patch::Remove( *num ) } )
+  .collect()
+}
+
+/// Allocates a single value to every request, keeping the total of allocated values within
+/// `budget`.
+///
+/// Requests are served in order. Every request takes the first value `attempt` selects for its
+/// preferences if the remaining budget covers it, or is downgraded to the largest allowed value
+/// below it that the remaining budget covers, or gets nothing if there is none. Allocated
+/// values are deducted from the budget. Assumes values are not negative.
+///
+/// Returns allocations in order of `requests`, each either a single value or empty.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let requests = [ vec![ Value::Number( 720 ) ], vec![ Value::Number( 720 ) ], vec![ Value::Number( 360 ) ] ];
+/// assert_eq!
+/// (
+///   allocate_batch( &available, &[ Value::Any ], &requests, 1200 ),
+///   vec![ vec![ 720 ], vec![ 360 ], vec![] ]
+/// );
+/// ```
+pub fn allocate_batch
+(
+  available : &[ i32 ],
+  allowed : &[ Value ],
+  requests : &[ Vec< Value > ],
+  budget : i64
+) -> Vec< Vec< i32 > >
+{
+  let available = sorted( available );
+  let ladder = reduce_by_allowed( &available, allowed );
+  let mut remaining = budget;
+  requests.iter()
+  .map( | preferred |
+  {
+    let Some( &top ) = attempt_sorted_unchecked( &available, allowed, preferred ).first() else
+    {
+      return vec![];
+    };
+    let affordable = ladder.partition_point( | num | *num <= top && i64::from( *num ) <= remaining );
+    let Some( &num ) = affordable.checked_sub( 1 ).map( | index | &ladder[ index ] ) else
+    {
+      return vec![];
+    };
+    remaining -= i64::from( num );
+    vec![ num ]
+  })
+  .collect()
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, pairing every
+/// value with a confidence in how well it matches the preference.
+///
+/// Confidence of a value selected by a `Value::Number` preference is `1 / ( 1 + distance / scale )`,
+/// so exact matches yield `1.0` and farther values approach `0.0` at a pace set by `scale`.
+/// Values selected by other preferences are matched as requested and yield `1.0`. Every value
+/// takes the confidence of the first preference that selects it. Returns values in the same
+/// order as `attempt`.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let preferred = [ Value::Number( 360 ), Value::Number( 620 ) ];
+/// assert_eq!( attempt_confident( &available, &[ Value::Any ], &preferred, 100.0 ), vec![ ( 360, 1.0 ), ( 720, 0.5 ) ] );
+/// ```
+pub fn attempt_confident< T : Distance >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  scale : f64
+) -> Vec< ( T, f64 ) >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  describe_selection( &ladder, preferred, | value, index | match value
+  {
+    ValueT::Number( target ) if ladder[ index ] != *target =>
+    {
+      1.0 / ( 1.0 + ladder[ index ].distance( *target ) as f64 / scale )
+    }
+    _ => 1.0,
+  })
+}
+
+/// Returns the first index of `0..len` for which `pred` fails, given that `pred` holds for a
+/// prefix of the indices, like `partition_point` of a slice that is never built.
+fn partition_index< F : Fn( usize ) -> bool >( len : usize, pred : F ) -> usize
+{
+  let ( mut low, mut high ) = ( 0, len );
+  while low < high
+  {
+    let middle = low + ( high - low ) / 2;
+    if pred( middle )
+    {
+      low = middle + 1;
+    }
+    else
+    {
+      high = middle;
+    }
+  }
+  low
+}
+
+/// Reduces `avaliable` ladder given as run-length encoded `ranges` by both `allowed` and
+/// `preferred` vector filters.
+///
+/// Every range `( start, end, step )` stands for values from `start` to `end` inclusive, taken
+/// with the `step`. Works like `attempt` on the ladder the ranges stand for, but never expands
+/// it: positions of values are computed arithmetically, so memory only grows with the number of
+/// ranges and selected values. Non-positive step is treated as `1`, and range with end less than
+/// start stands for no values.
+///
+/// Assumes `ranges` are sorted in ascending order and do not overlap.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let ranges = [ ( 240, 240, 1 ), ( 360, 2160, 120 ) ];
+/// let preferred = [ Value::Number( 1000 ), Value::AtMost( 300 ) ];
+/// assert_eq!( attempt_rle( &ranges, &[ Value::Any ], &preferred ), vec![ 1080, 240 ] );
+/// assert_eq!( attempt_rle( &ranges, &[ Value::AtMost( 720 ) ], &preferred ), vec![ 720, 240 ] );
+/// ```
+pub fn attempt_rle( ranges : &[ ( i32, i32, i32 ) ], allowed : &[ Value ], preferred : &[ Value ] ) -> Vec< i32 >
+{
+  // ranges as their first value, step, number of values, and position of the first value
+  let mut runs : Vec< ( i64, i64, usize, usize ) > = vec![];
+  let mut len = 0;
+  for ( start, end, step ) in ranges
+  {
+    if end < start
+    {
+      continue;
+    }
+    let step = i64::from( ( *step ).max( 1 ) );
+    let count = ( ( i64::from( *end ) - i64::from( *start ) ) / step ) as usize + 1;
+    runs.push( ( i64::from( *start ), step, count, len ) );
+    len += count;
+  }
+  let value = | position : usize |
+  {
+    let ( start, step, _, first ) = runs[ runs.partition_point( | run | run.3 <= position ) - 1 ];
+    ( start + step * ( position - first ) as i64 ) as i32
+  };
+  let below = | num : i32 | partition_index( len, | position | value( position ) < num );
+  let up_to = | num : i32 | partition_index( len, | position | value( position ) <= num );
+
+  // allowed values as spans of positions, along with position of the first allowed value of each
+  let mut merged : Vec< ( usize, usize, usize ) > = vec![];
+  let mut ladder_len = 0;
+  for ( start, end ) in allowed_spans( len, allowed, below, up_to, value )
+  {
+    merged.push( ( start, end, ladder_len ) );
+    ladder_len += end - start;
+  }
+  let rung = | index : usize |
+  {
+    let ( start, _, first ) = merged[ merged.partition_point( | span | span.2 <= index ) - 1 ];
+    value( start + index - first )
+  };
+  let rung_below = | num : i32 | partition_index( ladder_len, | index | rung( index ) < num );
+  let rung_up_to = | num : i32 | partition_index( ladder_len, | index | rung( index ) <= num );
+
+  if ladder_len == 0
+  {
+    return vec![];
+  }
+  if preferred.contains( &ValueT::Any )
+  {
+    return ( 0..ladder_len ).map( rung ).collect();
+  }
+  let mut seen = BTreeSet::new();
+  let mut vec = vec![];
+  for value in preferred
+  {
+    let ( lower, upper ) = match value
+    {
+      ValueT::Any => continue,
+      ValueT::Number( num ) => ( rung_below( *num ), rung_up_to( *num ) ),
+      ValueT::Range( start, end ) =>
+      {
+        let ( start, end ) = normalize_range( *start, *end );
+        ( rung_below( start ), rung_up_to( end ) )
+      }
+      ValueT::AtLeast( threshold ) =>
+      {
+        let index = rung_below( *threshold ).min( ladder_len - 1 );
+        ( index, index + 1 )
+      }
+      ValueT::AtMost( threshold ) =>
+      {
+        let index = rung_up_to( *threshold ).saturating_sub( 1 );
+        ( index, index + 1 )
+      }
+      ValueT::Index( index ) =>
+      {
+        let index = ( *index ).min( ladder_len - 1 );
+        ( index, index + 1 )
+      }
+    };
+    let ( lower, upper ) = if lower < upper
+    {
+      ( lower, upper )
+    }
+    else
+    {
+      let index = upper.min( ladder_len - 1 );
+      ( index, index + 1 )
+    };
+    for num in ( lower..upper ).map( rung )
+    {
+      if seen.insert( num )
+      {
+        vec.push( num );
+      }
+    }
+  }
+  vec
+}
+
+/// Finds a small set of allowed values that keeps every preference within `tol` of some of them.
+///
+/// Every `Value::Number` preference is covered by the allowed values at most `tol` away from
+/// it. Greedily takes the allowed value covering the most uncovered preferences, the smallest
+/// one on ties, until every preference that can be covered is. This is an approximation: the
+/// result covers the same preferences as the smallest possible set, but may have more values.
+/// Preferences farther than `tol` from every allowed value are left uncovered. Other kinds of
+/// preferences do not have a single value and are skipped. Returns values in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 480, 720, 1080 ];
+/// let preferred = [ Value::Number( 300 ), Value::Number( 400 ), Value::Number( 700 ), Value::Number( 2000 ) ];
+/// assert_eq!( min_cover( &available, &[ Value::Any ], &preferred, 60 ), vec![ 360, 720 ] );
+/// ```
+pub fn min_cover< T : Distance >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  tol : u64
+) -> Vec< T >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  let covers = | num : T, target : T | num.distance( target ) <= tol;
+  let mut uncovered : Vec< T > = preferred.iter()
+  .filter_map( | value | match value
+  {
+    ValueT::Number( num ) => Some( *num ),
+    _ => None,
+  })
+  .filter( | target | ladder.iter().any( | num | covers( *num, *target ) ) )
+  .collect();
+
+  let mut vec = vec![];
+  while !uncovered.is_empty()
+  {
+    let mut best = ( 0, ladder[ 0 ] );
+    for num in &ladder
+    {
+      let count = uncovered.iter().filter( | target | covers( *num, **target ) ).count();
+      if count > best.0
+      {
+        best = ( count, *num );
+      }
+    }
+    uncovered.retain( | target | !covers( best.1, *target ) );
+    vec.push( best.1 );
+  }
+  vec.sort();
+  vec
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, keeping the
+/// total of selected values within `cap`.
+///
+/// Works like `attempt`, then drops the least preferred values, from the end of the selection,
+/// until the sum of the remaining ones is not greater than `cap`. Returns empty vector if the
+/// most preferred value alone exceeds `cap`, in particular if even the smallest selected one
+/// does.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let preferred = [ Value::Number( 720 ), Value::Number( 240 ), Value::Number( 360 ) ];
+/// assert_eq!( attempt_sum_capped( &available, &[ Value::Any ], &preferred, 1000 ), vec![ 720, 240 ] );
+/// assert_eq!( attempt_sum_capped( &available, &[ Value::Any ], &preferred, 500 ), vec![] );
+/// ```
+pub fn attempt_sum_capped( available : &[ i32 ], allowed : &[ Value ], preferred : &[ Value ], cap : i64 ) -> Vec< i32 >
+{
+  let mut vec = attempt( available, allowed, preferred );
+  let mut total : i64 = vec.iter().map( | num | i64::from( *num ) ).sum();
+  while total > cap
+  {
+    match vec.pop()
+    {
+      Some( num ) => total -= i64::from( num ),
+      None => break,
+    }
+  }
+  vec
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, selecting at
+/// most `per_tier` values from every tier.
+///
+/// Every tier is an inclusive range of values, inverted tier is treated as if its bounds were
+/// swapped. Works like `attempt`, then drops values of tiers that already have `per_tier` values
+/// selected, so the most preferred values of every tier are kept. Value inside of overlapping
+/// tiers counts towards all of them and is kept only if none is full. Values outside of every
+/// tier are never dropped.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 480, 720, 1080, 2160 ];
+/// let tiers = [ ( 0, 480 ), ( 720, 1080 ), ( 2160, 2160 ) ];
+/// assert_eq!( attempt_tiered( &available, &tiers, &[ Value::Any ], &[ Value::Any ], 1 ), vec![ 240, 720, 2160 ] );
+/// ```
+pub fn attempt_tiered< T : Ord + Copy >
+(
+  available : &[ T ],
+  tiers : &[ ( T, T ) ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  per_tier : usize
+) -> Vec< T >
+{
+  let tiers : Vec< _ > = tiers.iter().map( | ( start, end ) | normalize_range( *start, *end ) ).collect();
+  let mut counts = vec![ 0; tiers.len() ];
+  let mut vec = attempt( available, allowed, preferred );
+  vec.retain( | num |
+  {
+    let inside : Vec< _ > = ( 0..tiers.len() ).filter( | tier | tiers[ *tier ].0 <= *num && *num <= tiers[ *tier ].1 ).collect();
+    if inside.iter().any( | tier | counts[ *tier ] >= per_tier )
+    {
+      return false;
+    }
+    inside.iter().for_each( | tier | counts[ *tier ] += 1 );
+    true
+  });
+  vec
+}
+
+/// Measures how evenly selections of a batch are spread across values.
+///
+/// Counts every value of every result, then returns Shannon entropy of their distribution in
+/// bits. Zero means every selection lands on the same value, and the entropy is the highest,
+/// the binary logarithm of the number of distinct values, when they are selected equally often.
+/// Returns zero if `results` select nothing.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// assert_eq!( selection_entropy( &[ vec![ 720 ], vec![ 720 ] ] ), 0.0 );
+/// assert_eq!( selection_entropy( &[ vec![ 240, 360 ], vec![ 720, 1080 ] ] ), 2.0 );
+/// ```
+#[ cfg( feature = "std" ) ]
+pub fn selection_entropy< T : Ord + Copy >( results : &[ Vec< T > ] ) -> f64
+{
+  let mut counts = BTreeMap::new();
+  for num in results.iter().flatten()
+  {
+    *counts.entry( *num ).or_insert( 0usize ) += 1;
+  }
+  let total : usize = counts.values().sum();
+  counts.values()
+  .map( | count | *count as f64 / total as f64 )
+  .map( | share | -share * share.log2() )
+  .sum::< f64 >()
+  .max( 0.0 )
+}
+
+/// Reduces original array by `preferred` vector and its size, letting `tiebreak` pick between
+/// equally close values.
+///
+/// Works like `reduce_by_preferred_with` with `TieBreak::PreferNearest`, but when the values
+/// below and above of the preferred value are equally close, `tiebreak` compares them and the
+/// one ordered first is returned, the lower one if they are ordered equal. Comparing values
+/// with `Ord::cmp` thus gives the same result as `TieBreak::PreferNearest`.
+///
+/// Returns copy of `original` if `preferred` contains `Value::Any`.
+///
+/// Assumes `original` is sorted in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let original = [ 360, 720 ];
+/// let preferred = [ Value::Number( 540 ) ];
+/// assert_eq!( reduce_by_preferred_tiebreak( &original, &preferred, | a, b | a.cmp( &b ) ), vec![ 360 ] );
+/// assert_eq!( reduce_by_preferred_tiebreak( &original, &preferred, | a, b | b.cmp( &a ) ), vec![ 720 ] );
+/// ```
+pub fn reduce_by_preferred_tiebreak< T, F >( original : &[ T ], preferred : &[ ValueT< T > ], tiebreak : F ) -> Vec< T >
+where
+  T : Distance,
+  F : Fn( T, T ) -> core::cmp::Ordering,
+{
+  if preferred.contains( &ValueT::Any )
+  {
+    return original.to_vec();
+  }
+  reduce_by_preferred_by
+  (
+    original,
+    preferred,
+    | ( start, end ), below, above |
+    {
+      let by_distance = original[ above ].distance( end ).cmp( &original[ below ].distance( start ) );
+      match by_distance.then_with( || tiebreak( original[ above ], original[ below ] ) )
+      {
+        core::cmp::Ordering::Less => above,
+        _ => below,
+      }
+    }
+  )
+}
+
+/// Reduces original array by `preferred` vector and its size, measuring closeness after
+/// applying `transform` to the values.
+///
+/// Works like `reduce_by_preferred_with` with `TieBreak::PreferNearest`, but distance between
+/// values is the absolute difference of their transformed values, so the closest value can be
+/// found on a logarithmic or a gamma corrected scale. The lower value is returned when both
+/// are equally close.
+///
+/// `transform` must be monotonically increasing, so that it preserves order of `original`
+/// values and binary search still finds the values around the preference.
+///
+/// Returns copy of `original` if `preferred` contains `Value::Any`.
+///
+/// Assumes `original` is sorted in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let original = [ 100, 1000 ];
+/// let preferred = [ Value::Number( 400 ) ];
+/// assert_eq!( reduce_by_preferred_with( &original, &preferred, TieBreak::PreferNearest ), vec![ 100 ] );
+/// assert_eq!( reduce_by_preferred_transform( &original, &preferred, | num | f64::from( num ).ln() ), vec![ 1000 ] );
+/// ```
+pub fn reduce_by_preferred_transform< T, F >( original : &[ T ], preferred : &[ ValueT< T > ], transform : F ) -> Vec< T >
+where
+  T : Ord + Copy,
+  F : Fn( T ) -> f64,
+{
+  if preferred.contains( &ValueT::Any )
+  {
+    return original.to_vec();
+  }
+  reduce_by_preferred_by
+  (
+    original,
+    preferred,
+    | ( start, end ), below, above |
+    {
+      if ( transform( original[ above ] ) - transform( end ) ).abs() < ( transform( start ) - transform( original[ below ] ) ).abs()
+      {
+        above
+      }
+      else
+      {
+        below
+      }
+    }
+  )
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, telling an
+/// empty selection apart from the lack of allowed values.
+///
+/// Works like `attempt`, but returns `Outcome::Selected` with the values it selects, or
+/// `Outcome::Empty` if it selects nothing, along with whether any available value is allowed.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// assert_eq!( attempt_outcome( &available, &[ Value::Any ], &[ Value::Number( 360 ) ] ), Outcome::Selected( vec![ 360 ] ) );
+/// assert_eq!( attempt_outcome( &available, &[ Value::Any ], &[] ), Outcome::Empty { had_candidates : true } );
+/// assert_eq!( attempt_outcome( &available, &[ Value::Number( 480 ) ], &[ Value::Any ] ), Outcome::Empty { had_candidates : false } );
+/// ```
+pub fn attempt_outcome< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Outcome< T >
+{
+  let available = sorted( available );
+  let vec = attempt_sorted_unchecked( &available, allowed, preferred );
+  if vec.is_empty()
+  {
+    return Outcome::Empty { had_candidates : !reduce_by_allowed( &available, allowed ).is_empty() };
+  }
+  Outcome::Selected( vec )
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, keeping selected
+/// values at least `min_gap` apart.
+///
+/// Works like `attempt`, then goes through the selection in order of preferences and skips
+/// every value closer than `min_gap` to a value kept before it.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 480, 720 ];
+/// let preferred = [ Value::Number( 480 ), Value::Number( 360 ), Value::Number( 240 ) ];
+/// assert_eq!( attempt_spread( &available, &[ Value::Any ], &preferred, 200 ), vec![ 480, 240 ] );
+/// ```
+pub fn attempt_spread< T : Distance >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  min_gap : u64
+) -> Vec< T >
+{
+  let mut vec : Vec< T > = vec![];
+  for num in attempt( available, allowed, preferred )
+  {
+    if vec.iter().all( | kept | kept.distance( num ) >= min_gap )
+    {
+      vec.push( num );
+    }
+  }
+  vec
+}
+
+/// Measures which part of the allowed values is selected.
+///
+/// Returns the number of distinct values `attempt` selects divided by the number of distinct
+/// available values that are allowed. Returns `0.0` if no available value is allowed.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 480, 720 ];
+/// assert_eq!( coverage_fraction( &available, &[ Value::Any ], &[ Value::Number( 360 ) ] ), 0.25 );
+/// assert_eq!( coverage_fraction( &available, &[ Value::AtLeast( 480 ) ], &[ Value::Any ] ), 1.0 );
+/// ```
+pub fn coverage_fraction< T : Ord + Copy >( available : &[ T ], allowed : &[ ValueT< T > ], preferred : &[ ValueT< T > ] ) -> f64
+{
+  let available = sorted( available );
+  let ladder : BTreeSet< _ > = reduce_by_allowed( &available, allowed ).into_iter().collect();
+  if ladder.is_empty()
+  {
+    return 0.0;
+  }
+  let selected : BTreeSet< _ > = attempt_sorted_unchecked( &available, allowed, preferred ).into_iter().collect();
+  selected.len() as f64 / ladder.len() as f64
+}
+
+/// Returns values of `original` that `allowed` contains, in ascending order without duplicates.
+///
+/// Gives the same values as `reduce_by_allowed` with every value of `allowed` wrapped into
+/// `Value::Number`, and serves it when there are many of them, but merges both vectors instead
+/// of searching for every allowed value. With `simd` feature enabled, compares every value of
+/// `original` against a fixed-size chunk of allowed values without branching. The compiler may
+/// auto-vectorize this comparison, depending on the target, but no explicit SIMD is used.
+/// Otherwise falls back to the scalar merge.
+///
+/// Assumes both `original` and `allowed` are sorted in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// assert_eq!( intersect_sorted_simd( &[ 240, 360, 480, 720 ], &[ 100, 360, 720, 1080 ] ), vec![ 360, 720 ] );
+/// ```
+pub fn intersect_sorted_simd< T : Ord + Copy >( original : &[ T ], allowed : &[ T ] ) -> Vec< T >
+{
+  let mut vec = vec![];
+  #[ cfg( feature = "simd" ) ]
+  let ( mut i, mut j ) = intersect_lanes( original, allowed, &mut vec );
+  #[ cfg( not( feature = "simd" ) ) ]
+  let ( mut i, mut j ) = ( 0, 0 );
+  while i < original.len() && j < allowed.len()
+  {
+    match original[ i ].cmp( &allowed[ j ] )
+    {
+      core::cmp::Ordering::Less => i += 1,
+      core::cmp::Ordering::Greater => j += 1,
+      core::cmp::Ordering::Equal =>
+      {
+        if vec.last() != Some( &original[ i ] )
+        {
+          vec.push( original[ i ] );
+        }
+        i += 1;
+      }
+    }
+  }
+  vec
+}
+
+/// Number of allowed values `intersect_lanes` compares at once.
+#[ cfg( feature = "simd" ) ]
+const LANES : usize = 8;
+
+/// Pushes values of `original` that `allowed` contains into `vec`, comparing every value
+/// against `LANES` values of `allowed` at once, and returns positions where both vectors are
+/// left to merge.
+#[ cfg( feature = "simd" ) ]
+fn intersect_lanes< T : Ord + Copy >( original : &[ T ], allowed : &[ T ], vec : &mut Vec< T > ) -> ( usize, usize )
+{
+  let ( mut i, mut j ) = ( 0, 0 );
+  while let ( Some( num ), Some( lanes ) ) = ( original.get( i ), allowed[ j.. ].first_chunk::< LANES >() )
+  {
+    if lanes[ LANES - 1 ] < *num
+    {
+      j += LANES;
+      continue;
+    }
+    if lanes.iter().fold( false, | found, lane | found | ( lane == num ) ) && vec.last() != Some( num )
+    {
+      vec.push( *num );
+    }
+    i += 1;
+  }
+  ( i, j )
+}
+
+/// Finds the preferred number the ladder serves best.
+///
+/// Every `Value::Number` preference is resolved against available values that are allowed,
+/// following the rules of `reduce_by_preferred`. Returns position of the preference closest to
+/// the value it resolves to, along with the value and the distance, the earlier one on equal
+/// distances. Other kinds of preferences do not have a single value and are skipped. Returns
+/// `None` if there are no preferred numbers or no available value is allowed.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let preferred = [ Value::Number( 300 ), Value::Number( 1080 ), Value::Number( 720 ) ];
+/// assert_eq!( best_served( &available, &[ Value::Any ], &preferred ), Some( ( 2, 720, 0 ) ) );
+/// ```
+pub fn best_served< T : Distance >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Option< ( usize, T, u64 ) >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  preferred.iter()
+  .enumerate()
+  .filter_map( | ( position, value ) | match value
+  {
+    ValueT::Number( num ) => resolve_number( &ladder, *num ).map( | index | ( position, ladder[ index ], ladder[ index ].distance( *num ) ) ),
+    _ => None,
+  })
+  .min_by_key( | ( _, _, distance ) | *distance )
+}
+
+/// Reduces every snapshot of `avaliable` vector by both `allowed` and `preferred` vector filters.
+///
+/// Every snapshot is a timestamp along with the values available at that time. Returns the
+/// timestamp of every snapshot along with values `attempt` selects from it, in order of
+/// `snapshots`.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let snapshots = [ ( 100, vec![ 240, 360 ] ), ( 200, vec![ 240, 360, 720 ] ) ];
+/// assert_eq!
+/// (
+///   attempt_over_snapshots( &snapshots, &[ Value::Any ], &[ Value::Number( 720 ) ] ),
+///   vec![ ( 100, vec![ 360 ] ), ( 200, vec![ 720 ] ) ]
+/// );
+/// ```
+pub fn attempt_over_snapshots< T : Ord + Copy >
+(
+  snapshots : &[ ( u64, Vec< T > ) ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Vec< ( u64, Vec< T > ) >
+{
+  snapshots.iter()
+  .map( | ( timestamp, available ) | ( *timestamp, attempt( available, allowed, preferred ) ) )
+  .collect()
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, making sure
+/// every selected value is in `contract`.
+///
+/// Works like `attempt`, but returns `ContractViolation` listing the selected values missing
+/// from `contract` instead of the selection, if there are any.
+///
+/// Assumes `contract` is sorted in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let contract = [ 360, 720 ];
+/// assert_eq!( attempt_contract( &available, &[ Value::Any ], &[ Value::Number( 720 ) ], &contract ), Ok( vec![ 720 ] ) );
+/// assert_eq!
+/// (
+///   attempt_contract( &available, &[ Value::Any ], &[ Value::Number( 240 ) ], &contract ),
+///   Err( ContractViolation { forbidden : vec![ 240 ] } )
+/// );
+/// ```
+pub fn attempt_contract< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  contract : &[ T ]
+) -> Result< Vec< T >, ContractViolation< T > >
+{
+  debug_assert!( contract.is_sorted(), "`contract` must be sorted in ascending order" );
+  let vec = attempt( available, allowed, preferred );
+  let forbidden : Vec< T > = vec.iter().copied().filter( | num | contract.binary_search( num ).is_err() ).collect();
+  if forbidden.is_empty()
+  {
+    Ok( vec )
+  }
+  else
+  {
+    Err( ContractViolation { forbidden } )
+  }
+}
+
+/// Finds the allowed value that keeps the farthest preferred number as close as possible.
+///
+/// Measures every available value that is allowed by its largest distance to any of the
+/// `Value::Number` preferences, and returns the value with the smallest one, the smaller value
+/// on equal distances. Other kinds of preferences do not have a single value and are skipped.
+/// Returns `None` if there are no preferred numbers or no available value is allowed.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720, 1080 ];
+/// let preferred = [ Value::Number( 240 ), Value::Number( 1080 ) ];
+/// assert_eq!( minimax_rung( &available, &[ Value::Any ], &preferred ), Some( 720 ) );
+/// ```
+pub fn minimax_rung< T : Distance >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Option< T >
+{
+  let numbers = preferred.iter().filter_map( | value | match value
+  {
+    ValueT::Number( num ) => Some( *num ),
+    _ => None,
+  });
+  let lowest = numbers.clone().min()?;
+  let highest = numbers.max()?;
+  reduce_by_allowed( &sorted( available ), allowed )
+  .into_iter()
+  .min_by_key( | num | num.distance( lowest ).max( num.distance( highest ) ) )
+}
+
+/// Finds the closest ladder values on both sides of every preferred number.
+///
+/// The ladder consists of available values that are allowed. Every `Value::Number` preference
+/// yields the largest ladder value not greater than it and the smallest ladder value not less
+/// than it, so an exact match is on both sides, and `None` on a side without ladder values.
+/// Other kinds of preferences do not have a single value and are skipped. Returns results in
+/// order of `preferred`.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let preferred = [ Value::Number( 360 ), Value::Number( 480 ), Value::Number( 1080 ) ];
+/// assert_eq!
+/// (
+///   attempt_brackets( &available, &[ Value::Any ], &preferred ),
+///   vec![ ( Some( 360 ), Some( 360 ) ), ( Some( 360 ), Some( 720 ) ), ( Some( 720 ), None ) ]
+/// );
+/// ```
+pub fn attempt_brackets< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ]
+) -> Vec< Gap< T > >
+{
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  preferred.iter()
+  .filter_map( | value | match value
+  {
+    ValueT::Number( num ) => Some( *num ),
+    _ => None,
+  })
+  .map( | num |
+  {
+    let lower = ladder.partition_point( | other | *other < num );
+    let upper = ladder.partition_point( | other | *other <= num );
+    ( upper.checked_sub( 1 ).map( | index | ladder[ index ] ), ladder.get( lower ).copied() )
+  })
+  .collect()
+}
+
+/// Hashes little-endian bytes of `keys` with FNV-1a starting from `seed`, used by
+/// `reduce_by_preferred_seeded` to break ties equally on every platform.
+fn seeded_hash( seed : u64, keys : &[ i128 ] ) -> u64
+{
+  let mut hash = 0xcbf2_9ce4_8422_2325 ^ seed;
+  for byte in keys.iter().flat_map( | key | key.to_le_bytes() )
+  {
+    hash = ( hash ^ u64::from( byte ) ).wrapping_mul( 0x0000_0100_0000_01b3 );
+  }
+  hash ^= hash >> 33;
+  hash = hash.wrapping_mul( 0xff51_afd7_ed55_8ccd );
+  hash ^ hash >> 33
+}
+
+/// Reduces original array by `preferred` vector and its size, breaking ties pseudo-randomly.
+///
+/// Works like `reduce_by_preferred_with` with `TieBreak::PreferNearest`, but when the values
+/// below and above of the preferred value are equally close, one of them is picked by a hash
+/// of the preferred value and `seed`. Equal preferences and seeds always pick the same value,
+/// on every platform, while ties of different preferences spread across both sides.
+///
+/// Returns copy of `original` if `preferred` contains `Value::Any`.
+///
+/// Assumes `original` is sorted in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let original = [ 360, 720 ];
+/// let preferred = [ Value::Number( 540 ) ];
+/// let picked = reduce_by_preferred_seeded( &original, &preferred, 7 );
+/// assert!( picked == vec![ 360 ] || picked == vec![ 720 ] );
+/// assert_eq!( reduce_by_preferred_seeded( &original, &preferred, 7 ), picked );
+/// assert_eq!( reduce_by_preferred_seeded( &original, &[ Value::Number( 400 ) ], 7 ), vec![ 360 ] );
+/// ```
+pub fn reduce_by_preferred_seeded< T >( original : &[ T ], preferred : &[ ValueT< T > ], seed : u64 ) -> Vec< T >
+where
+  T : Distance + StableKey,
+{
+  if preferred.contains( &ValueT::Any )
+  {
+    return original.to_vec();
+  }
+  reduce_by_preferred_by
+  (
+    original,
+    preferred,
+    | ( start, end ), below, above |
+    {
+      match original[ above ].distance( end ).cmp( &original[ below ].distance( start ) )
+      {
+        core::cmp::Ordering::Less => above,
+        core::cmp::Ordering::Greater => below,
+        core::cmp::Ordering::Equal =>
+        {
+          if seeded_hash( seed, &[ start.stable_key(), end.stable_key() ] ) & 1 == 1 { above } else { below }
+        }
+      }
+    }
+  )
+}
+
+/// Reduces `avaliable` vector by both `allowed` and `preferred` vector filters, substituting
+/// preferred numbers according to `overrides`.
+///
+/// Every override `( pref, target )` makes `Value::Number( pref )` preference select `target`
+/// regardless of distance, if `target` is available and allowed. Otherwise, and for preferences
+/// without overrides, works like `attempt`. The first override of a preferred number is used.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720, 1080 ];
+/// let preferred = [ Value::Number( 1080 ), Value::Number( 300 ) ];
+/// assert_eq!( attempt_with_overrides( &available, &[ Value::Any ], &preferred, &[ ( 1080, 720 ) ] ), vec![ 720, 360 ] );
+/// assert_eq!( attempt_with_overrides( &available, &[ Value::AtMost( 480 ) ], &preferred, &[ ( 1080, 720 ) ] ), vec![ 360 ] );
+/// ```
+pub fn attempt_with_overrides< T : Ord + Copy >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  preferred : &[ ValueT< T > ],
+  overrides : &[ ( T, T ) ]
+) -> Vec< T >
+{
+  let available = sorted( available );
+  let ladder = reduce_by_allowed( &available, allowed );
+  let preferred : Vec< _ > = preferred.iter()
+  .map( | value | match value
+  {
+    ValueT::Number( num ) => overrides.iter()
+    .find( | ( pref, _ ) | pref == num )
+    .filter( | ( _, target ) | ladder.binary_search( target ).is_ok() )
+    .map_or( *value, | ( _, target ) | ValueT::Number( *target ) ),
+    _ => *value,
+  })
+  .collect();
+  attempt_sorted_unchecked( &available, allowed, &preferred )
+}
+
+/// Measures how much the selection holds when available values are nudged.
+///
+/// Makes `samples` copies of the ladder, moving every value by a pseudo-random offset of at most
+/// `delta` either way, and selects from every copy like `attempt`. The ladder consists of
+/// available values that are allowed, so nudged values stay allowed. Values selected from a copy
+/// are mapped back to the values they were moved from, and the average `jaccard` similarity of
+/// these selections to the selection from `available` itself is returned, so `1.0` means nudging
+/// never changes which values are selected. Negative `delta` counts as its absolute value. Equal
+/// `seed` always gives equal offsets. Returns `1.0` if `samples` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let preferred = [ Value::Number( 360 ) ];
+/// assert_eq!( perturbation_stability( &available, &[ Value::Any ], &preferred, 0, 100, 7 ), 1.0 );
+/// assert!( perturbation_stability( &available, &[ Value::Any ], &preferred, 50, 100, 7 ) < 1.0 );
+/// assert_eq!( perturbation_stability( &available, &[ Value::Any ], &[ Value::Range( 300, 500 ) ], 50, 100, 7 ), 1.0 );
+/// ```
+pub fn perturbation_stability
+(
+  available : &[ i32 ],
+  allowed : &[ Value ],
+  preferred : &[ Value ],
+  delta : i32,
+  samples : usize,
+  seed : u64
+) -> f64
+{
+  if samples == 0
+  {
+    return 1.0;
+  }
+  let baseline = attempt( available, allowed, preferred );
+  let ladder = reduce_by_allowed( &sorted( available ), allowed );
+  let delta = delta.unsigned_abs();
+  let mut state = seed;
+  let mut next = | bound : u64 |
+  {
+    state = state.wrapping_add( 0x9e37_79b9_7f4a_7c15 );
+    let mut mixed = state;
+    mixed = ( mixed ^ mixed >> 30 ).wrapping_mul( 0xbf58_476d_1ce4_e5b9 );
+    mixed = ( mixed ^ mixed >> 27 ).wrapping_mul( 0x94d0_49bb_1331_11eb );
+    ( mixed ^ mixed >> 31 ) % bound
+  };
+  let total : f64 = ( 0..samples )
+  .map( | _ |
+  {
+    // pairs of nudged values and values they were moved from
+    let mut moved : Vec< ( i32, i32 ) > = ladder.iter()
+    .map( | num |
+    {
+      let offset = next( u64::from( delta ) * 2 + 1 ) as i64 - i64::from( delta );
+      ( ( i64::from( *num ) + offset ).clamp( i64::from( i32::MIN ), i64::from( i32::MAX ) ) as i32, *num )
+    })
+    .collect();
+    moved.sort();
+    let nudged : Vec< i32 > = moved.iter().map( | ( num, _ ) | *num ).collect();
+    let selected : Vec< i32 > = attempt_sorted_unchecked( &nudged, &[ ValueT::Any ], preferred )
+    .into_iter()
+    .map( | num | moved[ nudged.partition_point( | other | *other < num ) ].1 )
+    .collect();
+    jaccard( &baseline, &selected )
+  })
+  .sum();
+  total / samples as f64
+}
+
+/// Cheapest and second cheapest values of `costs[ s - 1 ]` for `attempt_curve` to switch from
+/// after `s` switches, as their costs and positions in the ladder.
+fn curve_cheapest( costs : &[ Vec< u64 > ], s : usize ) -> [ Option< ( u64, usize ) >; 2 ]
+{
+  let mut cheapest : [ Option< ( u64, usize ) >; 2 ] = [ None, None ];
+  if s > 0
+  {
+    for ( u, cost ) in costs[ s - 1 ].iter().enumerate()
+    {
+      if cheapest[ 0 ].is_none_or( | ( best, _ ) | *cost < best )
+      {
+        cheapest = [ Some( ( *cost, u ) ), cheapest[ 0 ] ];
+      }
+      else if cheapest[ 1 ].is_none_or( | ( best, _ ) | *cost < best )
+      {
+        cheapest[ 1 ] = Some( ( *cost, u ) );
+      }
+    }
+  }
+  cheapest
+}
+
+/// Returns the cheapest way for `attempt_curve` to reach ladder value `v` after `s` switches
+/// from `costs` of the previous target, as its cost and the previous ladder value, given the
+/// `cheapest` values to switch from. Staying at `v` is preferred on equal costs.
+fn curve_link( costs : &[ Vec< u64 > ], s : usize, v : usize, cheapest : &[ Option< ( u64, usize ) >; 2 ] ) -> ( u64, usize )
+{
+  let switch = if cheapest[ 0 ].is_some_and( | ( _, u ) | u == v ) { cheapest[ 1 ] } else { cheapest[ 0 ] };
+  match switch
+  {
+    Some( ( cost, u ) ) if cost < costs[ s ][ v ] => ( cost, u ),
+    _ => ( costs[ s ][ v ], v ),
+  }
+}
+
+/// Returns `costs` of `attempt_curve` after `target`, given `costs` after the previous one.
+///
+/// `costs[ s ][ v ]` is the smallest sum of distances for targets so far that ends at ladder
+/// value `v` after `s` switches.
+fn curve_step< T : Distance >( ladder : &[ T ], costs : &[ Vec< u64 > ], target : T ) -> Vec< Vec< u64 > >
+{
+  ( 0..costs.len() )
+  .map( | s |
+  {
+    let cheapest = curve_cheapest( costs, s );
+    ladder.iter()
+    .enumerate()
+    .map( | ( v, num ) | curve_link( costs, s, v, &cheapest ).0.saturating_add( num.distance( target ) ) )
+    .collect()
+  })
+  .collect()
+}
+
+/// Selects a ladder value for every target of a sequence, switching between values at most
+/// `max_switches` times.
+///
+/// The ladder consists of available values that are allowed. Returns a value for every target
+/// in order of `targets`, such that the value changes between consecutive targets at most
+/// `max_switches` times and the sum of distances between targets and their values is the
+/// smallest possible, preferring fewer switches among equal sums. With enough switches every
+/// target gets its nearest value. Returns empty vector if no available value is allowed.
+///
+/// Finds the sequence with dynamic programming over targets, switches and ladder values. The
+/// budget is capped by the number of switches the nearest values make, which is less than the
+/// number of targets, and the time is proportional to the product of the three. To keep memory
+/// low, only costs at every `√targets`-th target are kept, and the rest are recomputed once
+/// while reconstructing the sequence, so memory is proportional to `√targets` times the number
+/// of switches and ladder values.
+///
+/// # Examples
+///
+/// ```
+/// use attempt::*;
+///
+/// let available = [ 240, 360, 720 ];
+/// let targets = [ 700, 350, 710, 730 ];
+/// assert_eq!( attempt_curve( &available, &[ Value::Any ], &targets, 2 ), vec![ 720, 360, 720, 720 ] );
+/// assert_eq!( attempt_curve( &available, &[ Value::Any ], &targets, 0 ), vec![ 720, 720, 720, 720 ] );
+/// ```
+pub fn attempt_curve< T : Distance >
+(
+  available : &[ T ],
+  allowed : &[ ValueT< T > ],
+  targets : &[ T ],
+  max_switches : usize
+) -> Vec< T >
+{
+  let mut ladder = reduce_by_allowed( &sorted( available ), allowed );
+  ladder.dedup();
+  if ladder.is_empty() || targets.is_empty()
+  {
+    return vec![];
+  }
+
+  // more switches than the nearest values make can not make the sum any smaller
+  let nearest = | target : T |
+  {
+    let upper = ladder.partition_point( | num | *num < target );
+    match ( upper.checked_sub( 1 ), ladder.get( upper ) )
+    {
+      ( Some( below ), Some( above ) ) if above.distance( target ) < ladder[ below ].distance( target ) => upper,
+      ( Some( below ), _ ) => below,
+      _ => upper,
+    }
+  };
+  let nearest_switches = targets.windows( 2 ).filter( | pair | nearest( pair[ 0 ] ) != nearest( pair[ 1 ] ) ).count();
+  let switches = max_switches.min( nearest_switches );
+
+  let mut costs = vec![ vec![ u64::MAX; ladder.len() ]; switches + 1 ];
+  for ( v, num ) in ladder.iter().enumerate()
+  {
+    costs[ 0 ][ v ] = num.distance( targets[ 0 ] );
+  }
+  let block = targets.len().isqrt() + 1;
+  let mut checkpoints = vec![];
+  for ( t, target ) in targets.iter().enumerate().skip( 1 )
+  {
+    if ( t - 1 ) % block == 0
+    {
+      checkpoints.push( costs.clone() );
+    }
+    costs = curve_step( &ladder, &costs, *target );
+  }
+
+  let mut end = ( 0, 0 );
+  for s in 0..=switches
+  {
+    for v in 0..ladder.len()
+    {
+      if costs[ s ][ v ] < costs[ end.0 ][ end.1 ]
+      {
+        end = ( s, v );
+      }
+    }
+  }
+  let ( mut s, mut v ) = end;
+  let mut vec = vec![ ladder[ v ] ];
+  // checkpoint `k` holds costs after target `k * block`, which lead to the following `block` targets
+  for ( k, checkpoint ) in checkpoints.into_iter().enumerate().rev()
+  {
+    let first = k * block;
+    let last = ( first + block ).min( targets.len() - 1 );
+    let mut layers = vec![ checkpoint ];
+    for target in &targets[ first + 1..last ]
+    {
+      let next = curve_step( &ladder, &layers[ layers.len() - 1 ], *target );
+      layers.push( next );
+    }
+    for costs in layers.iter().rev()
+    {
+      let ( _, u ) = curve_link( costs, s, v, &curve_cheapest( costs, s ) );
+      if u != v
+      {
+        s -= 1;
+      }
+      v = u;
+      vec.push( ladder[ v ] );
+    }
+  }
+  vec.reverse();
+  vec
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::*;
+
+  #[ test ]
+  fn reducing_by_allowed() 
+  {
+    assert_eq!
+    (
+      reduce_by_allowed
+      (
+        &[ 240, 360, 720 ],
+        &[ Value::Number( 360 ), Value::Number( 720 ) ]
+      ),
+      vec![ 360, 720 ]
+    );
+
+    assert_eq!
+    (
+      reduce_by_allowed
+      (
+        &[ 240, 720 ],
+        &[ Value::Number( 360 ), Value::Number( 720 ) ]
+      ),
+      vec![ 720 ]
+    );
+
+    assert_eq!
+    (
+      reduce_by_allowed( &[ 240 ], &[ Value::Number( 360 ), Value::Number( 720 ) ] ),
+      vec![]
+    );
+
+    assert_eq!
+    (
+      reduce_by_allowed( &[ 240, 360 ], &[ Value::Number( 360 ), Value::Any ] ),
+      vec![ 240, 360 ]
+    );
+  }
+
+  #[ test ]
+  fn reducing_by_preferred() 
+  {
+    assert_eq!
+    (
+      reduce_by_preferred( &[ 240, 360, 720 ], &[ Value::Number( 360 ) ] ),
+      vec![ 360 ]
+    );
+
+    assert_eq!
+    (
+      reduce_by_preferred( &[ 360, 720 ], &[ Value::Number( 1080 ) ] ),
+      vec![ 720 ]
+    );
+
+    assert_eq!
+    (
+      reduce_by_preferred( &[ 360, 720 ], &[ Value::Any, Value::Number( 1080 ) ] ),
+      vec![ 360, 720 ]
+    );
+
+    assert_eq!
+    (
+      reduce_by_preferred
+      (
+        &[ 240, 360, 720 ],
+        &[ Value::Number( 240 ), Value::Number( 360 ) ]
+      ),
+      vec![ 240, 360 ]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred
+      (
+        &[ 240, 360, 720 ],
+        &[ Value::Number( 240 ), Value::Number( 1080 ) ]
+      ),
+      vec![ 240, 720 ]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred
+      (
+        &[ 240, 720 ],
+        &[ Value::Number( 240 ), Value::Number( 360 ) ]
+      ),
+      vec![ 240, 720 ]
+    );
+  }
+
+  #[ test ]
+  fn test_attempt() 
+  {
+    assert_eq!
+    (
+      attempt
+      (
+        &[ 240, 360, 720 ],
+        &[ Value::Number( 360 ), Value::Number( 720 ) ],
+        &[ Value::Number( 1080 ) ]
+      ),
+      vec![ 720 ]
+    );
+    assert_eq!
+    (
+      attempt
+      (
+        &[ 240, 720 ],
+        &[ Value::Number( 360 ), Value::Number( 720 ) ],
+        &[ Value::Number( 1080 ) ]
+      ),
+      vec![ 720 ]
+    );
+    assert_eq!
+    (
+      attempt
+      (
+        &[ 240 ],
+        &[ Value::Number( 360 ), Value::Number( 720 ) ],
+        &[ Value::Number( 1080 ) ]
+      ),
+      vec![]
+    );
+    assert_eq!
+    (
+      attempt
+      (
+        &[ 240, 360, 720 ],
+        &[ Value::Number( 240 ), Value::Number( 360 ), Value::Number( 720 ), Value::Number( 1080 ) ],
+        &[ Value::Number( 240 ), Value::Number( 360 ) ]
+      ),
+      vec![ 240, 360 ]
+    );
+    assert_eq!
+    (
+      attempt
+      (
+        &[ 240, 720 ],
+        &[ Value::Number( 240 ), Value::Number( 360 ), Value::Number( 720 ), Value::Number( 1080 ) ],
+        &[ Value::Number( 240 ), Value::Number( 360 ) ]
+      ),
+      vec![ 240, 720 ]
+    );
+    assert_eq!
+    (
+      attempt
+      (
+        &[ 240, 720 ],
+        &[ Value::Number( 240 ), Value::Number( 360 ), Value::Number( 1080 ) ],
+        &[ Value::Number( 240 ), Value::Number( 360 ) ]
+      ),
+      vec![ 240 ]
+    );
+    assert_eq!
+    (
+      attempt
+      (
+        &[ 720 ],
+        &[ Value::Number( 240 ), Value::Number( 360 ), Value::Number( 1080 ) ],
+        &[ Value::Number( 240 ), Value::Number( 360 ) ]
+      ),
+      vec![]
+    );
+    assert_eq!
+    (
+      attempt
+      (
+        &[ 240, 360 ],
+        &[ Value::Number( 240 ), Value::Number( 360 ) ],
+        &[ Value::Number( 720 ), Value::Number( 1080 ) ]
+      ),
+      vec![ 360 ]
+    );
+  }
+
+  #[ test ]
+  fn test_attempt_with_any() 
+  {
+    assert_eq!
+    (
+      attempt
+      (
+        &[ 240, 360, 720 ],
+        &[ Value::Number( 360 ), Value::Any ],
+        &[ Value::Number( 360 ), Value::Number( 720 ) ]
+      ),
+      vec![ 360, 720 ]
+    );
+    assert_eq!
+    (
+      attempt
+      (
+        &[ 240, 360, 720 ],
+        &[ Value::Number( 240 ), Value::Number( 360 ), Value::Number( 720 ) ],
+        &[ Value::Any, Value::Number( 720 ) ]
+      ),
+      vec![ 240, 360, 720 ]
+    );
+    assert_eq!
+    (
+      attempt
+      (
+        &[ 240, 360, 720 ],
+        &[ Value::Number( 360 ), Value::Number( 1080 ) ],
+        &[ Value::Any, Value::Number( 720 ) ]
+      ),
+      vec![ 360 ]
+    );
+    assert_eq!
+    (
+      attempt
+      (
+        &[ 240, 360, 720 ],
+        &[ Value::Number( 1080 ) ],
+        &[ Value::Any, Value::Number( 720 ) ]
+      ),
+      vec![]
+    );
+    assert_eq!
+    (
+      attempt( &[ 240, 360, 720 ], &[ Value::Any ], &[ Value::Any ] ),
+      vec![ 240, 360, 720 ]
+    );
+  }
+
+  #[ test ]
+  fn test_attempt_checked()
+  {
+    assert_eq!
+    (
+      attempt_checked
+      (
+        &[ 240, 360, 720 ],
+        &[ Value::Number( 360 ), Value::Number( 720 ) ],
+        &[ Value::Number( 1080 ) ]
+      ),
+      Ok( vec![ 720 ] )
+    );
+    assert_eq!
+    (
+      attempt_checked
+      (
+        &[ 720, 360, 240 ],
+        &[ Value::Number( 360 ), Value::Number( 720 ) ],
+        &[ Value::Number( 1080 ) ]
+      ),
+      Err( AttemptError::UnsortedInput )
+    );
+    assert_eq!
+    (
+      attempt_checked
+      (
+        &[ 240, 360, 720 ],
+        &[ Value::Number( 720 ), Value::Number( 360 ) ],
+        &[ Value::Number( 1080 ) ]
+      ),
+      Ok( vec![ 720 ] )
+    );
+    assert_eq!
+    (
+      attempt_checked( &[ 240, 360, 720 ], &[], &[ Value::Number( 360 ) ] ),
+      Err( AttemptError::EmptyAllowed )
+    );
+    assert_eq!
+    (
+      attempt_checked
+      (
+        &[ 240 ],
+        &[ Value::Number( 360 ), Value::Number( 720 ) ],
+        &[ Value::Number( 1080 ) ]
+      ),
+      Err( AttemptError::NoMatch )
+    );
+    assert_eq!
+    (
+      attempt_checked
+      (
+        &[ 240, 360, 720, 1080 ],
+        &[ Value::Any ],
+        &[ Value::Number( 1080 ), Value::Number( 240 ) ]
+      ),
+      Ok( vec![ 1080, 240 ] )
+    );
+  }
+
+  #[ test ]
+  fn test_attempt_unsorted()
+  {
+    assert_eq!
+    (
+      attempt( &[ 720, 360, 240 ], &[ Value::Any ], &[ Value::Any ] ),
+      vec![ 240, 360, 720 ]
+    );
+    assert_eq!
+    (
+      attempt
+      (
+        &[ 720, 240, 360 ],
+        &[ Value::Number( 360 ), Value::Number( 720 ) ],
+        &[ Value::Number( 1080 ) ]
+      ),
+      vec![ 720 ]
+    );
+    assert_eq!
+    (
+      attempt_sorted_unchecked
+      (
+        &[ 240, 360, 720 ],
+        &[ Value::Number( 240 ), Value::Number( 720 ) ],
+        &[ Value::Number( 360 ) ]
+      ),
+      vec![ 720 ]
+    );
+  }
+
+  #[ test ]
+  fn test_attempt_generic()
+  {
+    assert_eq!
+    (
+      attempt::< u64 >
+      (
+        &[ 3_000_000_000, 6_000_000_000, 9_000_000_000 ],
+        &[ ValueT::Number( 6_000_000_000 ), ValueT::Number( 9_000_000_000 ) ],
+        &[ ValueT::Number( 12_000_000_000 ) ]
+      ),
+      vec![ 9_000_000_000 ]
+    );
+    assert_eq!
+    (
+      attempt_checked::< u8 >( &[ 10, 20, 30 ], &[ ValueT::Any ], &[ ValueT::Number( 15 ) ] ),
+      Ok( vec![ 20 ] )
+    );
+  }
+
+  #[ test ]
+  fn test_attempt_with_range()
+  {
+    assert_eq!
+    (
+      reduce_by_allowed( &[ 240, 360, 720, 1080, 2160 ], &[ Value::Range( 360, 1080 ) ] ),
+      vec![ 360, 720, 1080 ]
+    );
+    assert_eq!
+    (
+      reduce_by_allowed( &[ 240, 360, 720, 1080, 2160 ], &[ Value::Range( 1080, 360 ) ] ),
+      vec![ 360, 720, 1080 ]
+    );
+    assert_eq!
+    (
+      reduce_by_allowed
+      (
+        &[ 240, 360, 720, 1080, 2160 ],
+        &[ Value::Number( 240 ), Value::Range( 240, 720 ), Value::Number( 720 ) ]
+      ),
+      vec![ 240, 360, 720 ]
+    );
+    assert_eq!
+    (
+      reduce_by_allowed( &[ 240, 2160 ], &[ Value::Range( 360, 1080 ) ] ),
+      vec![]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred( &[ 240, 360, 720, 1080 ], &[ Value::Range( 300, 800 ) ] ),
+      vec![ 360, 720 ]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred( &[ 240, 1080 ], &[ Value::Range( 360, 720 ) ] ),
+      vec![ 1080 ]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred( &[ 240, 360 ], &[ Value::Range( 720, 1080 ) ] ),
+      vec![ 360 ]
+    );
+    assert_eq!
+    (
+      attempt_checked
+      (
+        &[ 240, 360, 720, 1080 ],
+        &[ Value::Range( 360, 1080 ) ],
+        &[ Value::Number( 240 ), Value::Number( 1080 ) ]
+      ),
+      Ok( vec![ 360, 1080 ] )
+    );
+  }
+
+  #[ test ]
+  fn reducing_by_preferred_with()
+  {
+    assert_eq!
+    (
+      reduce_by_preferred_with( &[ 360, 720 ], &[ Value::Number( 600 ) ], TieBreak::PreferLower ),
+      vec![ 360 ]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred_with( &[ 360, 720 ], &[ Value::Number( 400 ) ], TieBreak::PreferHigher ),
+      vec![ 720 ]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred_with( &[ 360, 720 ], &[ Value::Number( 400 ) ], TieBreak::PreferNearest ),
+      vec![ 360 ]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred_with( &[ 360, 720 ], &[ Value::Number( 600 ) ], TieBreak::PreferNearest ),
+      vec![ 720 ]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred_with( &[ 360, 720 ], &[ Value::Number( 540 ) ], TieBreak::PreferNearest ),
+      vec![ 360 ]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred_with( &[ 360, 720 ], &[ Value::Number( 1080 ) ], TieBreak::PreferLower ),
+      vec![ 720 ]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred_with( &[ 360, 720 ], &[ Value::Number( 240 ) ], TieBreak::PreferLower ),
+      vec![ 360 ]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred_with::< u32 >
+      (
+        &[ 240, 1080 ],
+        &[ ValueT::Range( 360, 480 ) ],
+        TieBreak::PreferNearest
+      ),
+      vec![ 240 ]
+    );
+  }
+
+  #[ test ]
+  fn test_attempt_with_order()
+  {
+    let available = [ 240, 360, 720, 1080 ];
+    let allowed = [ Value::Any ];
+    let preferred = [ Value::Number( 720 ), Value::Number( 240 ), Value::Number( 1080 ) ];
+    assert_eq!
+    (
+      attempt_with_order( &available, &allowed, &preferred, OutputOrder::AsPreferred ),
+      vec![ 720, 240, 1080 ]
+    );
+    assert_eq!
+    (
+      attempt_with_order( &available, &allowed, &preferred, OutputOrder::Ascending ),
+      vec![ 240, 720, 1080 ]
+    );
+    assert_eq!
+    (
+      attempt_with_order( &available, &allowed, &preferred, OutputOrder::Descending ),
+      vec![ 1080, 720, 240 ]
+    );
+    assert_eq!
+    (
+      attempt_with_order( &[ 720, 240 ], &allowed, &[ Value::Any ], OutputOrder::Descending ),
+      vec![ 720, 240 ]
+    );
+  }
+
+  #[ test ]
+  fn reducing_by_preferred_interleaved_duplicates()
+  {
+    assert_eq!
+    (
+      reduce_by_preferred
+      (
+        &[ 240, 720 ],
+        &[ Value::Number( 240 ), Value::Number( 1080 ), Value::Number( 240 ) ]
+      ),
+      vec![ 240, 720 ]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred
+      (
+        &[ 240, 360, 720 ],
+        &[ Value::Number( 300 ), Value::Number( 240 ), Value::Number( 360 ), Value::Number( 200 ) ]
+      ),
+      vec![ 360, 240 ]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred
+      (
+        &[ 240, 360, 720 ],
+        &[ Value::Number( 360 ), Value::Range( 200, 400 ), Value::Number( 720 ) ]
+      ),
+      vec![ 360, 240, 720 ]
+    );
+  }
+
+  #[ test ]
+  fn test_attempt_iter()
+  {
+    let cases : Vec< ( Vec< i32 >, Vec< Value >, Vec< Value > ) > = vec!
+    [
+      ( vec![ 240, 360, 720 ], vec![ Value::Number( 360 ), Value::Number( 720 ) ], vec![ Value::Number( 1080 ) ] ),
+      ( vec![ 240, 720 ], vec![ Value::Number( 240 ), Value::Number( 360 ) ], vec![ Value::Number( 360 ) ] ),
+      ( vec![ 240 ], vec![ Value::Number( 360 ) ], vec![ Value::Number( 1080 ) ] ),
+      ( vec![ 240, 360, 720 ], vec![ Value::Any ], vec![ Value::Number( 300 ), Value::Number( 1080 ) ] ),
+      ( vec![ 240, 360, 720 ], vec![ Value::Number( 240 ), Value::Number( 720 ) ], vec![ Value::Any ] ),
+      ( vec![ 240, 240, 360 ], vec![ Value::Any ], vec![ Value::Any ] ),
+      ( vec![ 240, 240, 360 ], vec![ Value::Range( 200, 400 ) ], vec![ Value::Range( 200, 300 ) ] ),
+      (
+        vec![ 240, 360, 720, 1080 ],
+        vec![ Value::Range( 240, 720 ) ],
+        vec![ Value::Number( 1080 ), Value::Number( 240 ), Value::Number( 2160 ) ]
+      ),
+      (
+        vec![ 240, 360, 360, 720, 720, 1080 ],
+        vec![ Value::Index( 2 ), Value::Range( 700, 2000 ), Value::AtMost( 300 ) ],
+        vec![ Value::Range( 200, 800 ), Value::Range( 300, 1100 ), Value::Number( 500 ), Value::Index( 1 ) ]
+      ),
+      ( vec![ 240, 360, 360, 720 ], vec![ Value::Index( 2 ), Value::Index( 9 ) ], vec![ Value::Any ] ),
+    ];
+    for ( available, allowed, preferred ) in cases
+    {
+      assert_eq!
+      (
+        attempt_iter( &available, &allowed, &preferred ).collect::< Vec< _ > >(),
+        attempt( &available, &allowed, &preferred )
+      );
+    }
+
+    assert_eq!
+    (
+      attempt_iter( &[ 240, 360, 720 ], &[ Value::Any ], &[ Value::Any ] ).next(),
+      Some( 240 )
+    );
+  }
+
+  #[ test ]
+  fn reducing_by_weighted_preferred()
+  {
+    let weighted = | num, weight | WeightedValue { value : Value::Number( num ), weight };
+    assert_eq!
+    (
+      reduce_by_weighted_preferred( &[ 480, 720, 1080 ], &[ weighted( 720, 1.0 ), weighted( 1080, 3.0 ) ] ),
+      vec![ 1080, 720 ]
+    );
+    assert_eq!
+    (
+      reduce_by_weighted_preferred
+      (
+        &[ 480, 720, 1080 ],
+        &[ weighted( 1080, 3.0 ), weighted( 720, 2.0 ), weighted( 600, 2.0 ) ]
+      ),
+      vec![ 720, 1080 ]
+    );
+    assert_eq!
+    (
+      reduce_by_weighted_preferred
+      (
+        &[ 480, 720, 1080 ],
+        &[ weighted( 480, 1.0 ), weighted( 720, 0.5 ), weighted( 600, 0.5 ), weighted( 1080, 1.0 ) ]
+      ),
+      vec![ 480, 1080, 720 ]
+    );
+    assert_eq!
+    (
+      reduce_by_weighted_preferred
+      (
+        &[ 480, 720 ],
+        &[ weighted( 480, 1.0 ), WeightedValue { value : Value::Any, weight : 0.5 } ]
+      ),
+      vec![ 480, 720 ]
+    );
+    assert_eq!( reduce_by_weighted_preferred( &[], &[ weighted( 480, 1.0 ) ] ), vec![] );
+  }
+
+  #[ test ]
+  fn test_attempt_with_thresholds()
+  {
+    assert_eq!
+    (
+      reduce_by_allowed( &[ 240, 360, 720, 1080 ], &[ Value::AtLeast( 720 ) ] ),
+      vec![ 720, 1080 ]
+    );
+    assert_eq!
+    (
+      reduce_by_allowed( &[ 240, 360, 720, 1080 ], &[ Value::AtMost( 400 ), Value::Number( 360 ) ] ),
+      vec![ 240, 360 ]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred( &[ 240, 360, 720, 1080 ], &[ Value::AtMost( 1000 ) ] ),
+      vec![ 720 ]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred( &[ 360, 720 ], &[ Value::AtMost( 240 ) ] ),
+      vec![ 360 ]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred( &[ 240, 360, 720, 1080 ], &[ Value::AtLeast( 400 ) ] ),
+      vec![ 720 ]
+    );
+    assert_eq!
+    (
+      reduce_by_preferred( &[ 240, 360 ], &[ Value::AtLeast( 720 ) ] ),
+      vec![ 360 ]
+    );
+    assert_eq!
+    (
+      attempt( &[ 240, 360, 720, 1080 ], &[ Value::AtLeast( 360 ) ], &[ Value::AtMost( 300 ) ] ),
+      vec![ 360 ]
+    );
+    assert_eq!
+    (
+      attempt( &[ 240, 360, 720, 1080 ], &[ Value::AtLeast( 720 ) ], &[ Value::Any ] ),
+      vec![ 720, 1080 ]
+    );
+    assert_eq!
+    (
+      attempt( &[ 240, 360, 720, 1080 ], &[ Value::Any ], &[ Value::AtMost( 1080 ) ] ),
+      vec![ 1080 ]
+    );
+    assert_eq!
+    (
+      attempt_iter( &[ 240, 360, 720, 1080 ], &[ Value::AtMost( 720 ) ], &[ Value::AtLeast( 1080 ) ] )
+      .collect::< Vec< _ > >(),
+      vec![ 720 ]
+    );
+  }
+
+  #[ test ]
+  fn test_attempt_into()
+  {
+    let mut out = vec![ 1, 2, 3 ];
+    assert_eq!
+    (
+      attempt_into
+      (
+        &[ 240, 360, 720 ],
+        &[ Value::Number( 360 ), Value::Number( 720 ) ],
+        &[ Value::Number( 1080 ) ],
+        &mut out
+      ),
+      1
+    );
+    assert_eq!( out, vec![ 720 ] );
+
+    let capacity = out.capacity();
+    assert_eq!( attempt_into( &[ 240, 360, 720 ], &[ Value::Any ], &[ Value::Any ], &mut out ), 3 );
+    assert_eq!( out, vec![ 240, 360, 720 ] );
+    assert_eq!( out.capacity(), capacity );
+
+    assert_eq!( attempt_into( &[ 720, 240 ], &[ Value::Any ], &[ Value::Number( 300 ) ], &mut out ), 1 );
+    assert_eq!( out, vec![ 720 ] );
+
+    assert_eq!( attempt_into( &[ 240 ], &[ Value::Number( 360 ) ], &[ Value::Any ], &mut out ), 0 );
+    assert!( out.is_empty() );
+  }
+
+  #[ test ]
+  fn test_select_stable()
+  {
+    let available = [ 360, 480, 720, 1080 ];
+    let allowed = [ Value::Number( 360 ), Value::Number( 480 ), Value::Number( 720 ) ];
+    assert_eq!( select_stable( &available, &allowed, &[ Value::Number( 720 ) ], 480, 240 ), 480 );
+    assert_eq!( select_stable( &available, &allowed, &[ Value::Number( 720 ) ], 480, 239 ), 720 );
+    assert_eq!( select_stable( &available, &allowed, &[ Value::Number( 720 ) ], 720, 0 ), 720 );
+    assert_eq!( select_stable( &available, &allowed, &[ Value::Number( 720 ) ], 1080, 1000 ), 720 );
+    assert_eq!( select_stable( &available, &allowed, &[ Value::Number( 720 ) ], 600, 1000 ), 720 );
+    assert_eq!( select_stable( &available, &[ Value::Number( 240 ) ], &[ Value::Number( 720 ) ], 480, 0 ), 480 );
+  }
+
+  #[ test ]
+  fn test_restrict()
+  {
+    assert_eq!( restrict( &[ 720, 240, 1080 ], &[ 240, 360, 1080 ] ), vec![ 240, 1080 ] );
+    assert_eq!( restrict( &[ 720, 240 ], &[ 360 ] ), vec![] );
+    assert_eq!( restrict( &[], &[ 360 ] ), vec![] );
+    assert_eq!
+    (
+      restrict( &attempt( &[ 240, 360, 720 ], &[ Value::Any ], &[ Value::Any ] ), &[ 360, 720 ] ),
+      vec![ 360, 720 ]
+    );
+  }
+
+  #[ test ]
+  fn test_select_gradual()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let allowed = [ Value::Number( 240 ), Value::Number( 480 ), Value::Number( 720 ), Value::Number( 1080 ) ];
+    assert_eq!( select_gradual( &available, &allowed, &[ Value::Number( 1080 ) ], 240 ), 480 );
+    assert_eq!( select_gradual( &available, &allowed, &[ Value::Number( 1080 ) ], 720 ), 1080 );
+    assert_eq!( select_gradual( &available, &allowed, &[ Value::Number( 240 ) ], 1080 ), 720 );
+    assert_eq!( select_gradual( &available, &allowed, &[ Value::Number( 720 ) ], 720 ), 720 );
+    assert_eq!( select_gradual( &available, &allowed, &[ Value::Number( 1080 ) ], 360 ), 480 );
+    assert_eq!( select_gradual( &available, &allowed, &[ Value::Number( 240 ) ], 360 ), 240 );
+    assert_eq!( select_gradual( &available, &allowed, &[ Value::Number( 2160 ) ], 2160 ), 1080 );
+    assert_eq!( select_gradual( &available, &[ Value::Number( 2160 ) ], &[ Value::Number( 720 ) ], 360 ), 360 );
+  }
+
+  #[ test ]
+  fn test_selection_cost()
+  {
+    let available = [ 240, 360, 720 ];
+    assert_eq!( selection_cost( &available, &[ Value::Any ], &[ Value::Number( 240 ), Value::Number( 720 ) ] ), 0 );
+    assert_eq!( selection_cost( &available, &[ Value::Any ], &[ Value::Number( 300 ) ] ), 60 );
+    assert_eq!( selection_cost( &available, &[ Value::Any ], &[ Value::Number( 100 ) ] ), 140 );
+    assert_eq!( selection_cost( &available, &[ Value::Number( 240 ) ], &[ Value::Number( 720 ) ] ), 480 );
+    assert_eq!( selection_cost( &available, &[ Value::Any ], &[ Value::Any, Value::Range( 100, 200 ) ] ), 0 );
+    assert_eq!( selection_cost( &available, &[ Value::Number( 1080 ) ], &[ Value::Number( 720 ) ] ), 0 );
+  }
+
+  #[ cfg( feature = "csv" ) ]
+  #[ test ]
+  fn test_to_csv_row()
+  {
+    assert_eq!
+    (
+      to_csv_row
+      (
+        &[ 240, 360, 720 ],
+        &[ Value::Any ],
+        &[ Value::Number( 300 ), Value::Number( 720 ) ]
+      ),
+      "3,3,2,360;720,60"
+    );
+    assert_eq!( to_csv_row( &[ 240 ], &[ Value::Number( 360 ) ], &[ Value::Number( 360 ) ] ), "1,0,1,,0" );
+    assert_eq!( CSV_HEADER.split( ',' ).count(), to_csv_row( &[ 240 ], &[ Value::Any ], &[ Value::Any ] ).split( ',' ).count() );
+  }
+
+  #[ test ]
+  fn test_attempt_with_index()
+  {
+    let available = [ 240, 360, 720, 1080 ];
+    assert_eq!( reduce_by_allowed( &available, &[ Value::Index( 1 ), Value::Index( 7 ) ] ), vec![ 360 ] );
+    assert_eq!( reduce_by_preferred( &available, &[ Value::Index( 2 ), Value::Index( 7 ) ] ), vec![ 720, 1080 ] );
+    assert_eq!
+    (
+      attempt( &available, &[ Value::Number( 240 ), Value::Number( 720 ) ], &[ Value::Index( 1 ) ] ),
+      vec![ 720 ]
+    );
+    assert_eq!( attempt( &[ 720, 240, 360 ], &[ Value::Index( 0 ) ], &[ Value::Any ] ), vec![ 240 ] );
+    assert_eq!
+    (
+      attempt_iter( &available, &[ Value::AtLeast( 360 ) ], &[ Value::Index( 1 ), Value::Index( 9 ) ] )
+      .collect::< Vec< _ > >(),
+      vec![ 720, 1080 ]
+    );
+  }
+
+  #[ test ]
+  fn test_attempt_cyclic_index()
+  {
+    let available = [ 240, 360, 720, 1080 ];
+    assert_eq!( attempt_cyclic_index( &available, &[ Value::Any ], &[ Value::Index( 5 ) ] ), vec![ 360 ] );
+    assert_eq!( attempt_cyclic_index( &available, &[ Value::Any ], &[ Value::Index( 3 ) ] ), vec![ 1080 ] );
+    assert_eq!
+    (
+      attempt_cyclic_index( &available, &[ Value::AtLeast( 360 ) ], &[ Value::Index( 3 ), Value::Number( 720 ) ] ),
+      vec![ 360, 720 ]
+    );
+    assert_eq!( attempt_cyclic_index( &available, &[ Value::Number( 480 ) ], &[ Value::Index( 3 ) ] ), vec![] );
+  }
+
+  #[ test ]
+  fn test_diff_selections()
+  {
+    assert_eq!( diff_selections::< i32 >( &[], &[] ), SelectionDiff::default() );
+    assert_eq!
+    (
+      diff_selections( &[ 240, 360, 720 ], &[ 1080, 720, 480, 240 ] ),
+      SelectionDiff { added : vec![ 1080, 480 ], removed : vec![ 360 ], kept : vec![ 720, 240 ] }
+    );
+  }
+
+  #[ test ]
+  fn test_selection_delta()
+  {
+    let allowed = [ Value::AtMost( 1080 ) ];
+    let preferred = [ Value::Number( 720 ), Value::Number( 1080 ) ];
+    assert_eq!
+    (
+      selection_delta( &[ 360, 720, 1080 ], &[ 360, 720, 1080, 2160 ], &allowed, &preferred ),
+      SelectionDiff { added : vec![], removed : vec![], kept : vec![ 720, 1080 ] }
+    );
+    assert_eq!
+    (
+      selection_delta( &[ 360, 720, 1080 ], &[ 360, 1080 ], &allowed, &preferred ),
+      SelectionDiff { added : vec![], removed : vec![ 720 ], kept : vec![ 1080 ] }
+    );
+  }
+
+  #[ test ]
+  fn test_attempt_with_fallbacks()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let prefs =
+    [
+      PreferenceWithFallback { primary : 1080, fallback : 480 },
+      PreferenceWithFallback { primary : 600, fallback : 240 },
+      PreferenceWithFallback { primary : 720, fallback : 360 },
+      PreferenceWithFallback { primary : 2160, fallback : 1440 },
+      PreferenceWithFallback { primary : 240, fallback : 360 },
+    ];
+    assert_eq!( attempt_with_fallbacks( &available, &[ Value::Any ], &prefs ), vec![ 1080, 240, 720 ] );
+    assert_eq!( attempt_with_fallbacks( &available, &[ Value::AtMost( 720 ) ], &prefs ), vec![ 480, 240, 720 ] );
+    assert_eq!( attempt_with_fallbacks( &available, &[ Value::Number( 1440 ) ], &prefs ), vec![] );
+  }
+
+  #[ test ]
+  fn test_allowed_iter()
+  {
+    let available = [ 240, 360, 360, 720, 1080 ];
+    for allowed in
+    [
+      vec![ Value::Any ],
+      vec![ Value::Number( 360 ), Value::Number( 1080 ), Value::Number( 480 ) ],
+      vec![ Value::Range( 300, 800 ), Value::AtLeast( 1080 ) ],
+      vec![ Value::AtMost( 200 ) ],
+      vec![ Value::Index( 2 ) ],
+    ]
+    {
+      assert_eq!( allowed_iter( &available, &allowed ).collect::< Vec< _ > >(), reduce_by_allowed( &available, &allowed ) );
+    }
+    assert_eq!( allowed_iter( &available, &[ Value::AtLeast( 300 ) ] ).take( 1 ).collect::< Vec< _ > >(), vec![ 360 ] );
+  }
+
+  #[ test ]
+  fn test_quantize()
+  {
+    let available = [ 720, 240, 360 ];
+    assert_eq!( quantize( &available, 360.0 ), Some( 360 ) );
+    assert_eq!( quantize( &available, 539.9 ), Some( 360 ) );
+    assert_eq!( quantize( &available, 540.0 ), Some( 720 ) );
+    assert_eq!( quantize( &available, -1e9 ), Some( 240 ) );
+    assert_eq!( quantize( &available, 1e9 ), Some( 720 ) );
+    assert_eq!( quantize( &available, f64::NAN ), None );
+    assert_eq!( quantize( &[], 360.0 ), None );
+  }
+
+  #[ test ]
+  fn test_attempt_decayed()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let preferred = [ Value::Number( 720 ), Value::Range( 300, 500 ), Value::Number( 480 ), Value::Number( 1080 ) ];
+    assert_eq!( attempt_decayed( &available, &[ Value::Any ], &preferred, 0.5 ), vec![ 720, 360, 480, 1080 ] );
+    assert_eq!( attempt_decayed( &available, &[ Value::Any ], &preferred, 2.0 ), vec![ 1080, 480, 360, 720 ] );
+    assert_eq!( attempt_decayed( &available, &[ Value::Any ], &preferred, 1.0 ), vec![ 720, 360, 480, 1080 ] );
+    assert_eq!( attempt_decayed( &available, &[ Value::AtMost( 480 ) ], &preferred, 2.0 ), vec![ 480, 360 ] );
+  }
+
+  /// Deterministic xorshift generator returning numbers below `bound`, for randomized tests.
+  fn xorshift( seed : u64 ) -> impl FnMut( u64 ) -> u64
+  {
+    let mut state = seed;
+    move | bound |
+    {
+      state ^= state << 13;
+      state ^= state >> 7;
+      state ^= state << 17;
+      state % bound
+    }
+  }
+
+  /// Slow reference implementation of `attempt` built of linear scans only.
+  fn attempt_reference( available : &[ i32 ], allowed : &[ Value ], preferred : &[ Value ] ) -> Vec< i32 >
+  {
+    let mut available = available.to_vec();
+    available.sort();
+    let ladder : Vec< i32 > = if allowed.contains( &Value::Any )
+    {
+      available.clone()
+    }
+    else
+    {
+      let mut ladder = vec![];
+      for num in &available
+      {
+        let is_allowed = allowed.iter().any( | value | match value
+        {
+          Value::Any => true,
+          Value::Number( other ) => other == num,
+          Value::Range( start, end ) => start.min( end ) <= num && num <= start.max( end ),
+          Value::AtLeast( threshold ) => threshold <= num,
+          Value::AtMost( threshold ) => num <= threshold,
+          Value::Index( position ) => available.get( *position ) == Some( num ),
+        });
+        if is_allowed && !ladder.contains( num )
+        {
+          ladder.push( *num );
+        }
+      }
+      ladder
+    };
+    if ladder.is_empty() || preferred.contains( &Value::Any )
+    {
+      return ladder;
+    }
+
+    let first = ladder[ 0 ];
+    let last = ladder[ ladder.len() - 1 ];
+    let first_above = | bound : i32 | ladder.iter().copied().find( | num | *num > bound ).unwrap_or( last );
+    let mut result = vec![];
+    for value in preferred
+    {
+      let selected = match value
+      {
+        Value::Any => vec![],
+        Value::Number( num ) if ladder.contains( num ) => vec![ *num ],
+        Value::Number( num ) => vec![ first_above( *num ) ],
+        Value::Range( start, end ) =>
+        {
+          let ( start, end ) = ( *start.min( end ), *start.max( end ) );
+          let inside : Vec< i32 > = ladder.iter().copied().filter( | num | start <= *num && *num <= end ).collect();
+          if inside.is_empty() { vec![ first_above( end ) ] } else { inside }
+        }
+        Value::AtLeast( threshold ) => vec![ ladder.iter().copied().find( | num | num >= threshold ).unwrap_or( last ) ],
+        Value::AtMost( threshold ) => vec![ ladder.iter().copied().rfind( | num | num <= threshold ).unwrap_or( first ) ],
+        Value::Index( position ) => vec![ ladder.get( *position ).copied().unwrap_or( last ) ],
+      };
+      for num in selected
+      {
+        if !result.contains( &num )
+        {
+          result.push( num );
+        }
+      }
+    }
+    result
+  }
+
+  #[ test ]
+  fn test_attempt_against_reference()
+  {
+    let mut next = xorshift( 0x2545_f491_4f6c_dd1d );
+    let random_values = | next : &mut dyn FnMut( u64 ) -> u64 |
+    {
+      ( 0..next( 4 ) ).map( | _ | match next( 12 )
+      {
+        0 => Value::Any,
+        1 => Value::Range( next( 24 ) as i32, next( 24 ) as i32 ),
+        2 => Value::AtLeast( next( 24 ) as i32 ),
+        3 => Value::AtMost( next( 24 ) as i32 ),
+        4 => Value::Index( next( 8 ) as usize ),
+        _ => Value::Number( next( 24 ) as i32 ),
+      })
+      .collect::< Vec< _ > >()
+    };
+    for _ in 0..2000
+    {
+      let available : Vec< i32 > = ( 0..next( 8 ) ).map( | _ | next( 24 ) as i32 ).collect();
+      let allowed = random_values( &mut next );
+      let preferred = random_values( &mut next );
+      assert_eq!
+      (
+        attempt( &available, &allowed, &preferred ),
+        attempt_reference( &available, &allowed, &preferred ),
+        "available: {available:?}, allowed: {allowed:?}, preferred: {preferred:?}"
+      );
+    }
+  }
+
+  #[ test ]
+  fn test_attempt_dup_policy()
+  {
+    let available = [ 240, 360, 360, 720, 720, 720 ];
+    for ( allowed, preferred, collapsed, preserved ) in
+    [
+      ( vec![ Value::Any ], vec![ Value::Any ], vec![ 240, 360, 720 ], available.to_vec() ),
+      ( vec![ Value::Any ], vec![ Value::Index( 1 ) ], vec![ 360 ], vec![ 360, 360 ] ),
+      ( vec![ Value::AtLeast( 300 ) ], vec![ Value::Any ], vec![ 360, 720 ], vec![ 360, 360, 720, 720, 720 ] ),
+      ( vec![ Value::Any ], vec![ Value::Number( 700 ), Value::Number( 240 ) ], vec![ 720, 240 ], vec![ 720, 720, 720, 240 ] ),
+    ]
+    {
+      assert_eq!( attempt_dup_policy( &available, &allowed, &preferred, DuplicatePolicy::Collapse ), collapsed );
+      assert_eq!( attempt_dup_policy( &available, &allowed, &preferred, DuplicatePolicy::Preserve ), preserved );
+    }
+  }
+
+  #[ test ]
+  fn test_preferences_for()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let preferred =
+    [
+      Value::Number( 600 ),
+      Value::Range( 300, 500 ),
+      Value::AtMost( 400 ),
+      Value::Any,
+      Value::Number( 2160 ),
+    ];
+    assert_eq!( preferences_for( &available, &[ Value::Any ], &preferred, 360 ), vec![ 1, 2, 3 ] );
+    assert_eq!( preferences_for( &available, &[ Value::Any ], &preferred, 720 ), vec![ 0, 3 ] );
+    assert_eq!( preferences_for( &available, &[ Value::AtMost( 720 ) ], &preferred, 720 ), vec![ 0, 3, 4 ] );
+    assert_eq!( preferences_for( &available, &[ Value::AtMost( 720 ) ], &preferred, 1080 ), vec![] );
+    assert_eq!( preferences_for( &available, &[ Value::Any ], &preferred, 600 ), vec![] );
+  }
+
+  #[ test ]
+  fn test_attempt_timed()
+  {
+    let available = [ 240, 360, 720, 1080 ];
+    let allowed =
+    [
+      TimedAllowed { value : Value::Any, expires_at : 10 },
+      TimedAllowed { value : Value::Number( 1080 ), expires_at : 20 },
+      TimedAllowed { value : Value::AtMost( 360 ), expires_at : 30 },
+    ];
+    let preferred = [ Value::Number( 720 ) ];
+    assert_eq!( attempt_timed( &available, &allowed, &preferred, 0 ), vec![ 720 ] );
+    assert_eq!( attempt_timed( &available, &allowed, &preferred, 10 ), vec![ 1080 ] );
+    assert_eq!( attempt_timed( &available, &allowed, &preferred, 25 ), vec![ 360 ] );
+    assert_eq!( attempt_timed( &available, &allowed, &preferred, 30 ), vec![] );
+  }
+
+  #[ test ]
+  fn test_attempt_mask()
+  {
+    assert_eq!( attempt_mask( &[], &[ Value::Any ], &[ Value::Any ] ), vec![] );
+
+    let available : Vec< i32 > = ( 0..100 ).rev().collect();
+    let mask = attempt_mask( &available, &[ Value::Any ], &[ Value::Number( 99 ), Value::Range( 30, 35 ) ] );
+    assert_eq!( mask, vec![ 1, 0b111111 ] );
+    assert_eq!( mask_to_values( &available, &mask ), vec![ 99, 35, 34, 33, 32, 31, 30 ] );
+    let mask = attempt_mask( &available, &[ Value::AtMost( 10 ) ], &[ Value::Number( 0 ) ] );
+    assert_eq!( mask, vec![ 0, 1 << 35 ] );
+    assert_eq!( mask_to_values( &available, &mask ), vec![ 0 ] );
+    assert_eq!( mask_to_values( &[ 240, 360 ], &[] ), vec![] );
+  }
+
+  #[ test ]
+  fn test_ratchet_selector()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let mut selector = RatchetSelector::new();
+    assert_eq!( selector.floor(), None );
+    assert_eq!( selector.select( &[], &[ Value::Any ], &[ Value::Any ] ), None );
+    assert_eq!( selector.select( &available, &[ Value::Any ], &[ Value::Number( 400 ) ] ), Some( 480 ) );
+    assert_eq!( selector.select( &available, &[ Value::Any ], &[ Value::Number( 720 ) ] ), Some( 720 ) );
+    assert_eq!( selector.select( &available, &[ Value::Any ], &[ Value::Number( 240 ) ] ), Some( 720 ) );
+    assert_eq!
+    (
+      selector.select( &available, &[ Value::Number( 240 ), Value::Number( 1080 ) ], &[ Value::Number( 240 ) ] ),
+      Some( 1080 )
+    );
+    assert_eq!( selector.select( &available, &[ Value::AtMost( 720 ) ], &[ Value::Any ] ), None );
+    assert_eq!( selector.floor(), Some( 1080 ) );
+  }
+
+  #[ test ]
+  fn test_minimal_allowed_for()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let preferred = [ Value::Number( 300 ), Value::Number( 1080 ) ];
+    assert_eq!
+    (
+      minimal_allowed_for( &available, &preferred, &[ 360, 1080 ] ),
+      Some( vec![ Value::Number( 360 ), Value::Number( 1080 ) ] )
+    );
+    assert_eq!
+    (
+      minimal_allowed_for( &available, &preferred, &[ 480, 720 ] ),
+      Some( vec![ Value::Number( 480 ), Value::Number( 720 ) ] )
+    );
+    assert_eq!( minimal_allowed_for( &available, &preferred, &[ 240, 1080 ] ), None );
+    assert_eq!( minimal_allowed_for( &available, &preferred, &[ 600 ] ), None );
+    assert_eq!( minimal_allowed_for( &available, &preferred, &[ 720, 720 ] ), None );
+    assert_eq!( minimal_allowed_for( &available, &preferred, &[] ), Some( vec![] ) );
+  }
+
+  #[ test ]
+  fn test_attempt_with_steps()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    assert_eq!
+    (
+      attempt_with_steps( &available, &[ Value::Any ], &[ Value::Number( 600 ), Value::Number( 2160 ), Value::Number( 100 ) ] ),
+      vec![ ( 720, 1 ), ( 1080, -1 ), ( 240, 1 ) ]
+    );
+    assert_eq!
+    (
+      attempt_with_steps( &available, &[ Value::Number( 240 ), Value::Number( 1080 ) ], &[ Value::Range( 800, 400 ) ] ),
+      vec![ ( 1080, 1 ) ]
+    );
+    assert_eq!
+    (
+      attempt_with_steps( &available, &[ Value::Any ], &[ Value::Range( 400, 800 ), Value::AtLeast( 2000 ), Value::AtMost( 100 ) ] ),
+      vec![ ( 480, 0 ), ( 720, 0 ), ( 1080, -1 ), ( 240, 1 ) ]
+    );
+    assert_eq!
+    (
+      attempt_with_steps( &available, &[ Value::Any ], &[ Value::Index( 1 ), Value::Index( 7 ) ] ),
+      vec![ ( 360, 0 ), ( 1080, -3 ) ]
+    );
+    assert_eq!
+    (
+      attempt_with_steps( &available, &[ Value::AtLeast( 720 ) ], &[ Value::Any ] ),
+      vec![ ( 720, 0 ), ( 1080, 0 ) ]
+    );
+    for preferred in [ vec![ Value::Number( 600 ), Value::Range( 100, 400 ) ], vec![ Value::AtMost( 500 ), Value::Index( 9 ) ] ]
+    {
+      assert_eq!
+      (
+        attempt_with_steps( &available, &[ Value::Any ], &preferred ).into_iter().map( | ( num, _ ) | num ).collect::< Vec< _ > >(),
+        attempt( &available, &[ Value::Any ], &preferred )
+      );
+    }
+  }
+
+  #[ test ]
+  fn test_attempt_with_overflow()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let allowed = [ Value::Range( 360, 720 ) ];
+    let select = | preferred : &[ Value ], policy | attempt_with_overflow( &available, &allowed, preferred, policy );
+
+    let below = [ Value::Number( 240 ), Value::Number( 480 ) ];
+    assert_eq!( select( &below, OverflowPolicy::ClampToBound ), Ok( vec![ 360, 480 ] ) );
+    assert_eq!( select( &below, OverflowPolicy::Drop ), Ok( vec![ 480 ] ) );
+    assert_eq!( select( &below, OverflowPolicy::Error ), Err( AttemptError::OutOfRange ) );
+
+    let below = [ Value::AtMost( 300 ), Value::Range( 100, 200 ) ];
+    assert_eq!( select( &below, OverflowPolicy::ClampToBound ), Ok( vec![ 360 ] ) );
+    assert_eq!( select( &below, OverflowPolicy::Drop ), Ok( vec![] ) );
+    assert_eq!( select( &below, OverflowPolicy::Error ), Err( AttemptError::OutOfRange ) );
+
+    let above = [ Value::Number( 1080 ), Value::AtLeast( 800 ), Value::Index( 3 ), Value::Number( 400 ) ];
+    assert_eq!( select( &above, OverflowPolicy::ClampToBound ), Ok( vec![ 720, 480 ] ) );
+    assert_eq!( select( &above, OverflowPolicy::Drop ), Ok( vec![ 480 ] ) );
+    assert_eq!( select( &above, OverflowPolicy::Error ), Err( AttemptError::OutOfRange ) );
+
+    let inside = [ Value::Range( 200, 400 ), Value::AtLeast( 720 ), Value::AtMost( 360 ), Value::Index( 2 ) ];
+    assert_eq!( select( &inside, OverflowPolicy::Error ), select( &inside, OverflowPolicy::ClampToBound ) );
+    assert_eq!( select( &inside, OverflowPolicy::Drop ), Ok( vec![ 360, 720 ] ) );
+    assert_eq!( attempt_with_overflow( &[], &allowed, &below, OverflowPolicy::Error ), Ok( vec![] ) );
+  }
+
+  #[ test ]
+  fn test_attempt_windowed()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let preferred = [ Value::Number( 240 ), Value::Number( 720 ) ];
+    assert_eq!( attempt_windowed( &available, &[ Value::Any ], &preferred, 240..=1080 ), vec![ 240, 720 ] );
+    assert_eq!( attempt_windowed( &available, &[ Value::Any ], &preferred, 300..=500 ), vec![ 360, 480 ] );
+    assert_eq!( attempt_windowed( &available, &[ Value::Any ], &preferred, 800..=2000 ), vec![ 1080 ] );
+    assert_eq!( attempt_windowed( &available, &[ Value::AtMost( 480 ) ], &preferred, 300..=2000 ), vec![ 360, 480 ] );
+    assert_eq!( attempt_windowed( &available, &[ Value::Any ], &preferred, 500..=600 ), vec![] );
+    assert_eq!( attempt_windowed( &available, &[ Value::Any ], &preferred, RangeInclusive::new( 800, 300 ) ), vec![] );
+    assert_eq!( attempt_windowed( &available, &[ Value::Number( 240 ) ], &preferred, 300..=2000 ), vec![] );
+  }
+
+  #[ test ]
+  fn test_coverage_intervals()
+  {
+    let available = [ 240, 360, 360, 480, 720, 1080, 2160 ];
+    let preferred = [ Value::Range( 1000, 3000 ), Value::Number( 400 ), Value::Number( 240 ), Value::AtMost( 300 ) ];
+    assert_eq!
+    (
+      coverage_intervals( &available, &[ Value::Any ], &preferred ),
+      vec![ ( 240, 240, vec![ 2, 3 ] ), ( 480, 480, vec![ 1 ] ), ( 1080, 2160, vec![ 0 ] ) ]
+    );
+    assert_eq!
+    (
+      coverage_intervals( &available, &[ Value::Number( 240 ), Value::Number( 480 ), Value::Number( 1080 ) ], &preferred ),
+      vec![ ( 240, 1080, vec![ 0, 1, 2, 3 ] ) ]
+    );
+    assert_eq!
+    (
+      coverage_intervals( &available, &[ Value::AtMost( 720 ) ], &[ Value::Any ] ),
+      vec![ ( 240, 720, vec![ 0 ] ) ]
+    );
+    assert_eq!( coverage_intervals( &available, &[ Value::Any ], &[] ), vec![] );
+  }
+
+  #[ test ]
+  fn test_attempt_exact_or_gap()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let preferred = [ Value::Number( 100 ), Value::Any, Value::Number( 720 ), Value::Range( 0, 1000 ), Value::Number( 600 ) ];
+    assert_eq!
+    (
+      attempt_exact_or_gap( &available, &[ Value::Any ], &preferred ),
+      vec![ Err( ( None, Some( 240 ) ) ), Ok( 720 ), Err( ( Some( 480 ), Some( 720 ) ) ) ]
+    );
+    assert_eq!
+    (
+      attempt_exact_or_gap( &available, &[ Value::AtMost( 480 ) ], &preferred ),
+      vec![ Err( ( None, Some( 240 ) ) ), Err( ( Some( 480 ), None ) ), Err( ( Some( 480 ), None ) ) ]
+    );
+    assert_eq!
+    (
+      attempt_exact_or_gap( &available, &[ Value::Number( 1440 ) ], &preferred ),
+      vec![ Err( ( None, None ) ), Err( ( None, None ) ), Err( ( None, None ) ) ]
+    );
+  }
+
+  #[ test ]
+  fn test_attempt_efficient()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let preferred = [ Value::Number( 600 ), Value::Number( 400 ), Value::Range( 800, 1000 ) ];
+    for allowed in [ vec![ Value::Any ], vec![ Value::AtLeast( 360 ) ] ]
+    {
+      let ladder = reduce_by_allowed( &available, &allowed );
+      assert_eq!
+      (
+        attempt_efficient( &available, &allowed, &preferred, 1.0 ),
+        reduce_by_preferred_with( &ladder, &preferred, TieBreak::PreferNearest )
+      );
+    }
+    assert_eq!( attempt_efficient( &available, &[ Value::Any ], &preferred, 1.0 ), vec![ 480, 360, 720 ] );
+    assert_eq!( attempt_efficient( &available, &[ Value::Any ], &preferred, 0.4 ), vec![ 720, 480, 1080 ] );
+    assert_eq!( attempt_efficient( &available, &[ Value::Any ], &preferred, 4.0 ), vec![ 480, 360, 720 ] );
+    assert_eq!( attempt_efficient( &available, &[ Value::AtMost( 480 ) ], &[ Value::Any ], 4.0 ), vec![ 240, 360, 480 ] );
+  }
+
+  #[ test ]
+  fn test_attempt_with_remaining()
+  {
+    let available = [ 240, 360, 360, 720, 1080 ];
+    assert_eq!( attempt_with_remaining( &available, &[ Value::Any ], &[ Value::Any ] ), ( available.to_vec(), 0 ) );
+    assert_eq!
+    (
+      attempt_with_remaining( &available, &[ Value::Any ], &[ Value::Number( 360 ), Value::Number( 400 ) ] ),
+      ( vec![ 360, 720 ], 2 )
+    );
+    assert_eq!( attempt_with_remaining( &available, &[ Value::Number( 480 ) ], &[ Value::Any ] ), ( vec![], 0 ) );
+    assert_eq!( attempt_with_remaining( &available, &[ Value::Any ], &[] ), ( vec![], 4 ) );
+  }
+
+  #[ test ]
+  fn test_can_select()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let preferred = [ Value::Number( 300 ), Value::Number( 1080 ) ];
+    assert!( can_select( &available, &[ Value::Any ], &preferred, &[] ) );
+    assert!( can_select( &available, &[ Value::Any ], &preferred, &[ 1080, 360 ] ) );
+    assert!( can_select( &available, &[ Value::AtMost( 720 ) ], &preferred, &[ 720 ] ) );
+    assert!( !can_select( &available, &[ Value::AtMost( 720 ) ], &preferred, &[ 720, 1080 ] ) );
+    assert!( !can_select( &available, &[ Value::Number( 600 ) ], &preferred, &[ 600 ] ) );
+  }
+
+  #[ test ]
+  fn test_reduce_by_preferred_keyed()
+  {
+    let original = [ 240, 360, 480, 720 ];
+    let preferred = [ Value::Number( 300 ), Value::Number( 600 ), Value::Number( 400 ) ];
+    assert_eq!( reduce_by_preferred_keyed( &original, &preferred, &[ 0, 0, 0, 0 ] ), vec![ 360, 720 ] );
+    assert_eq!( reduce_by_preferred_keyed( &original, &preferred, &[ 1, 0, 1, 0 ] ), vec![ 240, 480, 360 ] );
+    assert_eq!( reduce_by_preferred_keyed( &original, &[ Value::Any ], &[ 0, 0, 0, 0 ] ), original.to_vec() );
+  }
+
+  #[ test ]
+  #[ should_panic ]
+  fn test_reduce_by_preferred_keyed_mismatch()
+  {
+    reduce_by_preferred_keyed( &[ 240, 360 ], &[ Value::Number( 300 ) ], &[ 1 ] );
+  }
+
+  #[ test ]
+  fn test_attempt_batch_packed()
+  {
+    let available = [ 1080, 240, 720, 360 ];
+    let allowed = [ Value::AtMost( 720 ) ];
+    let sets =
+    [
+      vec![ Value::Number( 1080 ), Value::Number( 300 ) ],
+      vec![ Value::Any ],
+      vec![ Value::Range( 200, 400 ) ],
+    ];
+    let ( values, offsets ) = attempt_batch_packed( &available, &allowed, &sets );
+    assert_eq!( offsets, vec![ 0, 2, 5, 7 ] );
+    for ( index, preferred ) in sets.iter().enumerate()
+    {
+      assert_eq!( values[ offsets[ index ]..offsets[ index + 1 ] ], attempt( &available, &allowed, preferred ) );
+    }
+    assert_eq!( attempt_batch_packed( &available, &allowed, &[] ), ( vec![], vec![ 0 ] ) );
+  }
+
+  #[ test ]
+  fn test_jaccard()
+  {
+    assert_eq!( jaccard( &[ 360, 720 ], &[ 720, 360 ] ), 1.0 );
+    assert_eq!( jaccard( &[ 360, 360, 720 ], &[ 720 ] ), 0.5 );
+    assert_eq!( jaccard( &[ 240 ], &[ 720 ] ), 0.0 );
+    assert_eq!( jaccard( &[ 240 ], &[] ), 0.0 );
+    assert_eq!( jaccard( &[ 240, 360, 480 ], &[ 360, 480, 720, 1080 ] ), 0.4 );
+  }
+
+  #[ test ]
+  fn test_annotate()
+  {
+    let available = [ 1080, 240, 720, 360 ];
+    let allowed = [ Value::AtMost( 720 ) ];
+    let preferred = [ Value::Number( 1080 ), Value::Range( 200, 400 ) ];
+    let info = annotate( &available, &allowed, &preferred );
+    assert_eq!
+    (
+      info,
+      vec!
+      [
+        RungInfo { value : 240, allowed : true, preferred_by : vec![ 1 ], selected : true },
+        RungInfo { value : 360, allowed : true, preferred_by : vec![ 1 ], selected : true },
+        RungInfo { value : 720, allowed : true, preferred_by : vec![ 0 ], selected : true },
+        RungInfo { value : 1080, allowed : false, preferred_by : vec![], selected : false },
+      ]
+    );
+    for rung in info
+    {
+      assert_eq!( rung.preferred_by, preferences_for( &available, &allowed, &preferred, rung.value ) );
+    }
+    let info = annotate( &available, &[ Value::Any ], &[ Value::Number( 360 ) ] );
+    assert_eq!( info.iter().filter( | rung | rung.allowed ).count(), 4 );
+    assert_eq!( info.iter().filter( | rung | rung.selected ).count(), 1 );
+  }
+
+  #[ test ]
+  fn test_attempt_spec()
+  {
+    let available = [ 1080, 240, 360, 480, 600, 720, 960 ];
+    let spec = AllowedSpec { min : 240, max : 960, step : 240 };
+    assert_eq!( attempt_spec( &available, &spec, &[ Value::Any ] ), vec![ 240, 480, 720, 960 ] );
+    assert_eq!( attempt_spec( &available, &spec, &[ Value::Number( 1080 ), Value::Number( 500 ) ] ), vec![ 960, 720 ] );
+    assert_eq!
+    (
+      attempt_spec( &available, &spec, &[ Value::Range( 300, 800 ) ] ),
+      attempt( &available, &[ Value::Number( 240 ), Value::Number( 480 ), Value::Number( 720 ), Value::Number( 960 ) ], &[ Value::Range( 300, 800 ) ] )
+    );
+    let spec = AllowedSpec { min : -100, max : 100, step : 0 };
+    assert!( spec.allows( -100 ) );
+    assert!( !spec.allows( 100 ) );
+    assert_eq!( attempt_spec( &[ -100, 0, 100 ], &spec, &[ Value::Number( 0 ) ] ), vec![ -100 ] );
+    assert!( AllowedSpec { min : -100, max : 100, step : 50 }.allows( 0 ) );
+    assert!( !AllowedSpec { min : 100, max : -100, step : 50 }.allows( 0 ) );
+  }
+
+  #[ test ]
+  fn test_attempt_clustered()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let preferred =
+    [
+      Value::Number( 1000 ),
+      Value::Number( 200 ),
+      Value::Any,
+      Value::Number( 300 ),
+      Value::Number( 400 ),
+      Value::Number( 1100 ),
+    ];
+    assert_eq!( attempt_clustered( &available, &[ Value::Any ], &preferred, 100 ), vec![ 1080, 360 ] );
+    assert_eq!( attempt_clustered( &available, &[ Value::Any ], &preferred, 0 ), vec![ 1080, 240, 360 ] );
+    assert_eq!( attempt_clustered( &available, &[ Value::Any ], &preferred, 1000 ), vec![ 720 ] );
+    assert_eq!( attempt_clustered( &available, &[ Value::AtMost( 720 ) ], &preferred, 100 ), vec![ 720, 360 ] );
+    assert_eq!( attempt_clustered( &available, &[ Value::Number( 600 ) ], &preferred, 100 ), vec![] );
+    assert_eq!( attempt_clustered( &available, &[ Value::Any ], &[ Value::Any ], 100 ), vec![] );
+  }
+
+  #[ test ]
+  fn test_attempt_by_cost()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let preferred = [ Value::Number( 1440 ), Value::Number( 400 ), Value::Range( 200, 300 ), Value::Number( 720 ) ];
+    assert_eq!
+    (
+      attempt_by_cost( &available, &[ Value::Any ], &preferred ),
+      vec![ ( 240, 0 ), ( 720, 0 ), ( 480, 80 ), ( 1080, 360 ) ]
+    );
+    assert_eq!
+    (
+      attempt_by_cost( &available, &[ Value::AtLeast( 360 ) ], &[ Value::Number( 360 ), Value::Range( 300, 400 ) ] ),
+      vec![ ( 360, 0 ) ]
+    );
+    assert_eq!
+    (
+      attempt_by_cost( &available, &[ Value::AtMost( 360 ) ], &[ Value::Any ] ),
+      vec![ ( 240, 0 ), ( 360, 0 ) ]
+    );
+  }
+
+  #[ test ]
+  fn test_attempt_or_all()
+  {
+    let available = [ 720, 240, 360, 1080 ];
+    assert_eq!( attempt_or_all( &available, &[ Value::Any ], &[] ), vec![ 240, 360, 720, 1080 ] );
+    assert_eq!( attempt_or_all( &available, &[ Value::Range( 300, 800 ) ], &[] ), vec![ 360, 720 ] );
+    assert_eq!( attempt_or_all( &available, &[ Value::Range( 300, 800 ) ], &[ Value::Number( 1080 ) ] ), vec![ 720 ] );
+    assert_eq!( attempt_or_all( &available, &[ Value::Number( 480 ) ], &[] ), vec![] );
+  }
+
+  #[ test ]
+  fn test_attempt_distribution()
+  {
+    let available = [ 240, 360, 480, 720 ];
+    let targets = [ ( 720, 0.5 ), ( 480, 0.3 ), ( 240, 0.2 ) ];
+    assert_eq!( attempt_distribution( &available, &[ Value::Any ], &targets, 10 ).len(), 10 );
+    assert_eq!( attempt_distribution( &available, &[ Value::Any ], &targets, 3 ), vec![ 720, 480, 240 ] );
+    assert_eq!( attempt_distribution( &available, &[ Value::Any ], &targets, 0 ), vec![] );
+    assert_eq!
+    (
+      attempt_distribution( &available, &[ Value::AtMost( 480 ) ], &targets, 5 ),
+      vec![ 480, 480, 480, 480, 240 ]
+    );
+    assert_eq!
+    (
+      attempt_distribution( &available, &[ Value::Any ], &[ ( 300, 1.0 ), ( 360, 1.0 ), ( 720, -1.0 ), ( 240, f64::NAN ) ], 2 ),
+      vec![ 360, 360 ]
+    );
+    assert_eq!( attempt_distribution( &available, &[ Value::Any ], &[ ( 720, 0.0 ) ], 4 ), vec![] );
+    assert_eq!( attempt_distribution( &available, &[ Value::Number( 1080 ) ], &targets, 4 ), vec![] );
+  }
+
+  #[ test ]
+  fn test_slider_selector()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let allowed = [ Value::Range( 300, 800 ) ];
+    let selector = SliderSelector::new( &available, &allowed );
+    let cases =
+    [
+      ( 0, 360 ), ( 360, 360 ), ( 400, 360 ), ( 420, 480 ), ( 480, 480 ), ( 100, 360 ), ( 481, 480 ),
+      ( 600, 720 ), ( 599, 480 ), ( 720, 720 ), ( 721, 720 ), ( 2000, 720 ), ( 300, 360 ), ( 419, 360 ),
+    ];
+    for ( preferred, expected ) in cases
+    {
+      assert_eq!( selector.on_value( preferred ), Some( expected ), "preferred: {preferred}" );
+    }
+    assert_eq!( SliderSelector::new( &[ 240, 240, 720 ], &[ Value::Any ] ).on_value( 250 ), Some( 240 ) );
+    assert_eq!( SliderSelector::new( &available, &[ Value::Number( 600 ) ] ).on_value( 600 ), None );
+  }
+
+  #[ test ]
+  fn test_attempt_classified_each()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let preferred =
+    [
+      Value::Range( 300, 400 ),
+      Value::Range( 500, 600 ),
+      Value::AtLeast( 2000 ),
+      Value::AtMost( 100 ),
+      Value::Index( 9 ),
+      Value::Number( 1080 ),
+    ];
+    assert_eq!
+    (
+      attempt_classified_each( &available, &[ Value::Any ], &preferred ),
+      vec!
+      [
+        ( 360, MatchKind::Exact ),
+        ( 720, MatchKind::RoundedUp ),
+        ( 1080, MatchKind::ClampedHigh ),
+        ( 240, MatchKind::ClampedLow ),
+      ]
+    );
+    assert_eq!
+    (
+      attempt_classified_each( &available, &[ Value::AtLeast( 400 ) ], &[ Value::Index( 1 ), Value::AtMost( 500 ) ] ),
+      vec![ ( 720, MatchKind::Exact ), ( 480, MatchKind::Exact ) ]
+    );
+    assert_eq!
+    (
+      attempt_classified_each( &available, &[ Value::AtMost( 360 ) ], &[ Value::Any ] ),
+      vec![ ( 240, MatchKind::Exact ), ( 360, MatchKind::Exact ) ]
+    );
+  }
+
+  #[ test ]
+  fn test_expand_range_preference()
+  {
+    let available = [ 720, 240, 360, 360, 480, 1080 ];
+    assert_eq!
+    (
+      expand_range_preference( &available, &[ Value::Any ], 300, 720 ),
+      vec![ Value::Number( 360 ), Value::Number( 480 ), Value::Number( 720 ) ]
+    );
+    assert_eq!
+    (
+      expand_range_preference( &available, &[ Value::Number( 240 ), Value::Number( 720 ) ], 720, 0 ),
+      vec![ Value::Number( 240 ), Value::Number( 720 ) ]
+    );
+    assert_eq!( expand_range_preference( &available, &[ Value::Any ], 500, 700 ), vec![] );
+    assert_eq!( expand_range_preference( &available, &[ Value::Index( 4 ) ], 0, 2000 ), vec![ Value::Number( 720 ) ] );
+    assert_eq!
+    (
+      attempt( &available, &[ Value::Any ], &expand_range_preference( &available, &[ Value::Any ], 300, 720 ) ),
+      attempt( &available, &[ Value::Any ], &[ Value::Range( 300, 720 ) ] )
+    );
+  }
+
+  #[ test ]
+  fn test_attempt_report()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let preferred =
+    [
+      Value::Number( 360 ),
+      Value::Any,
+      Value::Number( 400 ),
+      Value::Number( 240 ),
+      Value::Number( 2160 ),
+      Value::Number( 200 ),
+      Value::Range( 0, 100 ),
+    ];
+    assert_eq!
+    (
+      attempt_report( &available, &[ Value::AtLeast( 300 ) ], &preferred ),
+      vec!
+      [
+        PrefOutcome::Matched { pref : 360, chosen : 360, exact : true },
+        PrefOutcome::Matched { pref : 400, chosen : 480, exact : false },
+        PrefOutcome::Unmet { pref : 240, reason : UnmetReason::NotAllowed },
+        PrefOutcome::Unmet { pref : 2160, reason : UnmetReason::AboveLadder },
+        PrefOutcome::Unmet { pref : 200, reason : UnmetReason::BelowLadder },
+      ]
+    );
+    assert_eq!
+    (
+      attempt_report( &available, &[ Value::Number( 600 ) ], &[ Value::Number( 600 ) ] ),
+      vec![ PrefOutcome::Unmet { pref : 600, reason : UnmetReason::NotAllowed } ]
+    );
+  }
+
+  #[ test ]
+  fn test_closest_n()
+  {
+    let original = [ 240, 360, 360, 480, 720 ];
+    assert_eq!( closest_n( &original, 360, 10 ), vec![ 360, 480, 240, 720 ] );
+    assert_eq!( closest_n( &original, 600, 2 ), vec![ 720, 480 ] );
+    assert_eq!( closest_n( &original, 2000, 2 ), vec![ 720, 480 ] );
+    assert_eq!( closest_n( &original, 0, 1 ), vec![ 240 ] );
+    assert_eq!( closest_n( &original, 0, 0 ), vec![] );
+    assert_eq!( closest_n( &[], 0, 3 ), vec![] );
+  }
+
+  #[ test ]
+  fn test_reduce_by_kth_nearest()
+  {
+    let original = [ 240, 360, 480, 720 ];
+    let preferred = [ Value::Number( 400 ), Value::Any, Value::Number( 720 ), Value::Number( 240 ) ];
+    assert_eq!( reduce_by_kth_nearest( &original, &preferred, 1 ), vec![ 360, 720, 240 ] );
+    assert_eq!( reduce_by_kth_nearest( &original, &preferred, 2 ), vec![ 480, 360 ] );
+    assert_eq!( reduce_by_kth_nearest( &original, &preferred, 4 ), vec![ 720, 240 ] );
+    assert_eq!( reduce_by_kth_nearest( &original, &preferred, 5 ), vec![] );
+    assert_eq!( reduce_by_kth_nearest( &original, &preferred, 0 ), vec![] );
+  }
+
+  #[ test ]
+  fn test_attempt_audited()
+  {
+    let available = [ 1080, 240, 360, 720 ];
+    let allowed = [ Value::AtMost( 720 ) ];
+    let preferred = [ Value::Number( 1080 ), Value::Number( 720 ), Value::Range( 200, 400 ) ];
+    let mut log = AuditLog::new();
+    assert_eq!( attempt_audited( &available, &allowed, &preferred, &mut log ), attempt( &available, &allowed, &preferred ) );
+    assert_eq!
+    (
+      log.events(),
+      &[
+        AuditEvent::Allowed { value : 240, allowed : true },
+        AuditEvent::Allowed { value : 360, allowed : true },
+        AuditEvent::Allowed { value : 720, allowed : true },
+        AuditEvent::Allowed { value : 1080, allowed : false },
+        AuditEvent::Selected { preference : 0, value : 720, kind : MatchKind::ClampedHigh },
+        AuditEvent::Collapsed { preference : 1, value : 720 },
+        AuditEvent::Selected { preference : 2, value : 240, kind : MatchKind::Exact },
+        AuditEvent::Selected { preference : 2, value : 360, kind : MatchKind::Exact },
+      ]
+    );
+
+    let mut log = AuditLog::new();
+    let preferred = [ Value::Number( 240 ), Value::Any ];
+    assert_eq!( attempt_audited( &available, &allowed, &preferred, &mut log ), attempt( &available, &allowed, &preferred ) );
+    assert_eq!
+    (
+      log.events()[ 4.. ],
+      [ 240, 360, 720 ].map( | value | AuditEvent::Selected { preference : 1, value, kind : MatchKind::Exact } )
+    );
+
+    let available = [ 720, 240, 720, 240, 360 ];
+    for preferred in [ &[ Value::Number( 720 ), Value::Number( 240 ) ][ .. ], &[ Value::Any ] ]
+    {
+      let mut log = AuditLog::new();
+      assert_eq!( attempt_audited( &available, &[ Value::Any ], preferred, &mut log ), attempt( &available, &[ Value::Any ], preferred ) );
+      assert_eq!
+      (
+        log.events()[ ..3 ],
+        [ 240, 360, 720 ].map( | value | AuditEvent::Allowed { value, allowed : true } )
+      );
+      assert!( log.events()[ 3.. ].iter().all( | event | !matches!( event, AuditEvent::Allowed { .. } ) ) );
+    }
+    let mut log = AuditLog::new();
+    assert_eq!( attempt_audited( &available, &[ Value::Any ], &[ Value::Any ], &mut log ), vec![ 240, 240, 360, 720, 720 ] );
+    assert_eq!
+    (
+      log.events()[ 3.. ],
+      [ 240, 360, 720 ].map( | value | AuditEvent::Selected { preference : 0, value, kind : MatchKind::Exact } )
+    );
+  }
+
+  #[ test ]
+  fn test_preference_profile()
+  {
+    let mut registry = ProfileRegistry::new();
+    assert!( registry.insert( PreferenceProfile::new( "mobile", vec![ Value::Number( 360 ) ] ) ).is_none() );
+    let replaced = registry.insert( PreferenceProfile::new( "mobile", vec![ Value::Number( 480 ), Value::Number( 240 ) ] ) );
+    assert_eq!( replaced.map( | profile | profile.preferred ), Some( vec![ Value::Number( 360 ) ] ) );
+    registry.insert( PreferenceProfile::new( "desktop", vec![ Value::Any ] ) );
+
+    let available = [ 720, 240, 360, 1080 ];
+    for name in [ "mobile", "desktop" ]
+    {
+      let profile = registry.get( name ).unwrap();
+      assert_eq!
+      (
+        profile.apply( &available, &[ Value::AtMost( 720 ) ] ),
+        attempt( &available, &[ Value::AtMost( 720 ) ], &profile.preferred )
+      );
+    }
+    assert_eq!( registry.get( "mobile" ).unwrap().apply( &available, &[ Value::Any ] ), vec![ 720, 240 ] );
+    assert!( registry.remove( "desktop" ).is_some() );
+    assert!( registry.get( "desktop" ).is_none() );
+  }
+
+  #[ test ]
+  fn test_allowed_set()
+  {
+    let available = [ 1080, 240, 360, 360, 720 ];
+    let allowed = [ Value::Range( 300, 800 ) ];
+    let preferred = [ Value::Number( 1080 ), Value::Number( 200 ) ];
+    let mut set = AllowedSet::new( &available, &allowed );
+    assert_eq!( set.ladder(), &[ 360, 720 ] );
+    assert_eq!( set.select( &preferred ), attempt( &available, &allowed, &preferred ) );
+
+    assert!( set.insert( 480 ) );
+    assert!( !set.insert( 720 ) );
+    assert_eq!( set.ladder(), &[ 360, 480, 720 ] );
+    assert_eq!( set.select( &[ Value::Number( 400 ) ] ), vec![ 480 ] );
+
+    assert!( set.remove( 360 ) );
+    assert!( !set.remove( 240 ) );
+    assert_eq!( set.select( &preferred ), vec![ 720, 480 ] );
+    assert_eq!( set.select( &[ Value::Any ] ), vec![ 480, 720 ] );
+  }
+
+  #[ test ]
+  fn test_best_allowed_policy()
   {
-    return reduce_by_preferred( available, preferred );
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let preferred = [ Value::Number( 400 ), Value::Number( 1000 ) ];
+    let candidates =
+    [
+      vec![ Value::Number( 1440 ) ],
+      vec![ Value::AtMost( 480 ) ],
+      vec![ Value::Number( 480 ), Value::Number( 1080 ) ],
+      vec![ Value::Any ],
+    ];
+    assert_eq!( best_allowed_policy( &available, &candidates, &preferred ), Some( ( 2, vec![ 480, 1080 ] ) ) );
+    assert_eq!( best_allowed_policy( &available, &candidates[ ..2 ], &preferred ), Some( ( 1, vec![ 480 ] ) ) );
+    assert_eq!( best_allowed_policy( &available, &candidates[ ..1 ], &preferred ), Some( ( 0, vec![] ) ) );
+    assert_eq!( best_allowed_policy( &available, &[], &preferred ), None );
   }
-  if preferred.contains( &Value::Any ) 
+
+  #[ test ]
+  fn test_allowed_set_select_excluding()
   {
-    return reduce_by_allowed( available, allowed );
+    let set = AllowedSet::new( &[ 240, 360, 480, 720, 1080 ], &[ Value::AtLeast( 300 ) ] );
+    let preferred = [ Value::Number( 480 ), Value::Number( 2160 ) ];
+    assert_eq!( set.select_excluding( &preferred, &[] ), set.select( &preferred ) );
+    assert_eq!( set.select_excluding( &preferred, &[ 240, 480 ] ), vec![ 720, 1080 ] );
+    assert_eq!( set.select_excluding( &preferred, &[ 480, 720, 1080 ] ), vec![ 360 ] );
+    assert_eq!( set.select_excluding( &preferred, &[ 360, 480, 720, 1080 ] ), vec![] );
+    assert_eq!( set.ladder(), &[ 360, 480, 720, 1080 ] );
   }
-  let available = &reduce_by_allowed( available, allowed );
-  reduce_by_preferred( available, preferred )
-}
 
-#[ cfg( test ) ]
-mod tests
-{
-  use super::*;
+  #[ test ]
+  fn test_selection_sensitivity()
+  {
+    let available = [ 240, 360, 480, 720 ];
+    assert_eq!( selection_sensitivity( &available, &[ Value::Any ], 360 ), Some( 1 ) );
+    assert_eq!( selection_sensitivity( &available, &[ Value::Any ], 100 ), Some( 141 ) );
+    assert_eq!( selection_sensitivity( &available, &[ Value::Any ], 600 ), Some( 120 ) );
+    assert_eq!( selection_sensitivity( &available, &[ Value::Any ], 2000 ), Some( 1520 ) );
+    assert_eq!( selection_sensitivity( &available, &[ Value::AtLeast( 400 ) ], 240 ), Some( 241 ) );
+    assert_eq!( selection_sensitivity( &[ 360, 360 ], &[ Value::Any ], 240 ), None );
+    assert_eq!( selection_sensitivity( &available, &[ Value::Number( 1080 ) ], 240 ), None );
+    for pref in [ 100, 360, 420, 600, 2000 ]
+    {
+      let change = selection_sensitivity( &available, &[ Value::Any ], pref ).unwrap() as i32;
+      let selected = | pref | attempt( &available, &[ Value::Any ], &[ Value::Number( pref ) ] );
+      assert!( selected( pref + change ) != selected( pref ) || selected( pref - change ) != selected( pref ) );
+      for smaller in 1..change.min( 50 )
+      {
+        assert_eq!( selected( pref + smaller ), selected( pref ) );
+        assert_eq!( selected( pref - smaller ), selected( pref ) );
+      }
+    }
+  }
 
   #[ test ]
-  fn reducing_by_allowed() 
+  fn test_attempt_map_prefs()
   {
+    let available = [ 240, 360, 480, 720 ];
+    let preferred = [ Value::Number( 1080 ), Value::Any, Value::Number( 400 ), Value::Number( 1080 ), Value::Number( 240 ) ];
     assert_eq!
     (
-      reduce_by_allowed
-      (
-        &vec![ 240, 360, 720 ],
-        &vec![ Value::Number( 360 ), Value::Number( 720 ) ]
-      ),
-      vec![ 360, 720 ]
+      attempt_map_prefs( &available, &[ Value::AtLeast( 300 ) ], &preferred ),
+      BTreeMap::from( [ ( 240, 360 ), ( 400, 480 ), ( 1080, 720 ) ] )
     );
+    assert_eq!( attempt_map_prefs( &available, &[ Value::Number( 1080 ) ], &preferred ), BTreeMap::new() );
+  }
 
-    assert_eq!
-    (
-      reduce_by_allowed
-      (
-        &vec![ 240, 720 ],
-        &vec![ Value::Number( 360 ), Value::Number( 720 ) ]
-      ),
-      vec![ 720 ]
-    );
+  #[ cfg( feature = "futures" ) ]
+  #[ test ]
+  fn test_select_stream()
+  {
+    use futures::StreamExt;
 
-    assert_eq!
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let allowed = [ Value::AtMost( 720 ) ];
+    let prefs = [ 100, 480, 500, 2000 ];
+    let selected : Vec< _ > = futures::executor::block_on
     (
-      reduce_by_allowed( &vec![ 240 ], &vec![ Value::Number( 360 ), Value::Number( 720 ) ] ),
-      vec![]
+      select_stream( AllowedSet::new( &available, &allowed ), futures::stream::iter( prefs ) ).collect()
     );
+    let expected : Vec< _ > = prefs.iter()
+    .map( | pref | attempt( &available, &allowed, &[ Value::Number( *pref ) ] )[ 0 ] )
+    .collect();
+    assert_eq!( selected, expected );
+
+    let empty = AllowedSet::new( &available, &[ Value::Number( 600 ) ] );
+    let selected : Vec< _ > = futures::executor::block_on( select_stream( empty, futures::stream::iter( prefs ) ).collect() );
+    assert_eq!( selected, vec![] );
   }
 
   #[ test ]
-  fn reducing_by_preferred() 
+  fn test_worst_served()
+  {
+    let available = [ 240, 360, 480, 720 ];
+    let preferred = [ Value::Number( 400 ), Value::Range( 0, 10000 ), Value::Number( 600 ), Value::Number( 840 ) ];
+    assert_eq!( worst_served( &available, &[ Value::Any ], &preferred ), Some( ( 600, 120 ) ) );
+    assert_eq!( worst_served( &available, &[ Value::AtMost( 480 ) ], &preferred ), Some( ( 840, 360 ) ) );
+    assert_eq!( worst_served( &available, &[ Value::Any ], &[ Value::Number( 240 ), Value::Any ] ), None );
+    assert_eq!( worst_served( &available, &[ Value::Number( 1080 ) ], &preferred ), None );
+  }
+
+  #[ test ]
+  fn test_reduce_by_preferred_home()
+  {
+    let original = [ 240, 360, 480, 720 ];
+    let preferred = [ Value::Number( 300 ), Value::Number( 600 ), Value::Number( 400 ) ];
+    assert_eq!( reduce_by_preferred_home( &original, &preferred, 720 ), vec![ 360, 720 ] );
+    assert_eq!( reduce_by_preferred_home( &original, &preferred, 0 ), vec![ 240, 480, 360 ] );
+    assert_eq!( reduce_by_preferred_home( &original, &[ Value::Number( 300 ) ], 300 ), vec![ 240 ] );
+    assert_eq!( reduce_by_preferred_home( &original, &[ Value::Number( 100 ), Value::Number( 1080 ) ], 480 ), vec![ 240, 720 ] );
+    assert_eq!( reduce_by_preferred_home( &original, &[ Value::Any ], 480 ), original.to_vec() );
+  }
+
+  #[ test ]
+  fn test_selection_patch()
+  {
+    assert_eq!( selection_patch( &[ 360, 720 ], &[ 720, 360, 360 ] ), vec![] );
+    assert_eq!( selection_patch( &[], &[ 720, 360 ] ), vec![ Patch::Add( 360 ), Patch::Add( 720 ) ] );
+    assert_eq!( selection_patch( &[ 720, 360 ], &[] ), vec![ Patch::Remove( 360 ), Patch::Remove( 720 ) ] );
+
+    let old = [ 240, 480, 720 ];
+    let new = [ 1080, 360, 480 ];
+    let mut patched = old.to_vec();
+    for patch in selection_patch( &old, &new )
+    {
+      match patch
+      {
+        Patch::Add( num ) => patched.push( num ),
+        Patch::Remove( num ) => patched.retain( | other | *other != num ),
+      }
+    }
+    patched.sort();
+    assert_eq!( patched, vec![ 360, 480, 1080 ] );
+  }
+
+  #[ test ]
+  fn test_allocate_batch()
   {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let requests =
+    [
+      vec![ Value::Number( 1080 ) ],
+      vec![ Value::Number( 720 ), Value::Number( 240 ) ],
+      vec![ Value::Range( 300, 500 ) ],
+      vec![],
+      vec![ Value::Number( 240 ) ],
+    ];
     assert_eq!
     (
-      reduce_by_preferred( &vec![ 240, 360, 720 ], &vec![ Value::Number( 360 ) ] ),
-      vec![ 360 ]
+      allocate_batch( &available, &[ Value::Any ], &requests, 10000 ),
+      vec![ vec![ 1080 ], vec![ 720 ], vec![ 360 ], vec![], vec![ 240 ] ]
     );
-
     assert_eq!
     (
-      reduce_by_preferred( &vec![ 360, 720 ], &vec![ Value::Number( 1080 ) ] ),
-      vec![ 720 ]
+      allocate_batch( &available, &[ Value::Any ], &requests, 1800 ),
+      vec![ vec![ 1080 ], vec![ 720 ], vec![], vec![], vec![] ]
     );
-
     assert_eq!
     (
-      reduce_by_preferred
-      (
-        &vec![ 240, 360, 720 ],
-        &vec![ Value::Number( 240 ), Value::Number( 360 ) ]
-      ),
-      vec![ 240, 360 ]
+      allocate_batch( &available, &[ Value::Any ], &requests, 2100 ),
+      vec![ vec![ 1080 ], vec![ 720 ], vec![ 240 ], vec![], vec![] ]
     );
     assert_eq!
     (
-      reduce_by_preferred
-      (
-        &vec![ 240, 360, 720 ],
-        &vec![ Value::Number( 240 ), Value::Number( 1080 ) ]
-      ),
-      vec![ 240, 720 ]
+      allocate_batch( &available, &[ Value::Any ], &requests, 1700 ),
+      vec![ vec![ 1080 ], vec![ 480 ], vec![], vec![], vec![] ]
     );
     assert_eq!
     (
-      reduce_by_preferred
-      (
-        &vec![ 240, 720 ],
-        &vec![ Value::Number( 240 ), Value::Number( 360 ) ]
-      ),
-      vec![ 240, 720 ]
+      allocate_batch( &available, &[ Value::AtMost( 720 ) ], &requests, 1000 ),
+      vec![ vec![ 720 ], vec![ 240 ], vec![], vec![], vec![] ]
     );
+    assert_eq!( allocate_batch( &available, &[ Value::Any ], &requests, 0 ), vec![ vec![]; 5 ] );
   }
 
+
   #[ test ]
-  fn test_attempt() 
+  fn test_attempt_confident()
   {
+    let available = [ 240, 360, 480, 720, 1080 ];
     assert_eq!
     (
-      attempt
-      (
-        &vec![ 240, 360, 720 ],
-        &vec![ Value::Number( 360 ), Value::Number( 720 ) ],
-        &vec![ Value::Number( 1080 ) ]
-      ),
-      vec![ 720 ]
+      attempt_confident( &available, &[ Value::Any ], &[ Value::Number( 480 ), Value::Number( 320 ) ], 40.0 ),
+      vec![ ( 480, 1.0 ), ( 360, 0.5 ) ]
     );
     assert_eq!
     (
-      attempt
-      (
-        &vec![ 240, 720 ],
-        &vec![ Value::Number( 360 ), Value::Number( 720 ) ],
-        &vec![ Value::Number( 1080 ) ]
-      ),
-      vec![ 720 ]
+      attempt_confident( &available, &[ Value::AtMost( 720 ) ], &[ Value::Number( 1000 ), Value::Range( 300, 400 ) ], 280.0 ),
+      vec![ ( 720, 0.5 ), ( 360, 1.0 ) ]
     );
     assert_eq!
     (
-      attempt
-      (
-        &vec![ 240 ],
-        &vec![ Value::Number( 360 ), Value::Number( 720 ) ],
-        &vec![ Value::Number( 1080 ) ]
-      ),
-      vec![]
+      attempt_confident( &available, &[ Value::Any ], &[ Value::Number( 240 ), Value::Number( 300 ) ], 20.0 ),
+      vec![ ( 240, 1.0 ), ( 360, 0.25 ) ]
     );
+    assert_eq!( attempt_confident( &available, &[ Value::Any ], &[ Value::Any ], 1.0 ).len(), 5 );
+    assert_eq!( attempt_confident( &available, &[ Value::Number( 100 ) ], &[ Value::Any ], 1.0 ), vec![] );
+    assert_eq!( attempt_confident( &available, &[ Value::Any ], &[], 1.0 ), vec![] );
+  }
+
+
+  #[ test ]
+  fn test_attempt_rle()
+  {
+    let ranges = [ ( 240, 240, 1 ), ( 360, 2160, 120 ) ];
+    let available : Vec< i32 > = core::iter::once( 240 ).chain( ( 360..=2160 ).step_by( 120 ) ).collect();
+    let cases =
+    [
+      ( vec![ Value::Any ], vec![ Value::Any ] ),
+      ( vec![ Value::Any ], vec![ Value::Number( 1000 ), Value::Number( 100 ), Value::Number( 5000 ) ] ),
+      ( vec![ Value::AtMost( 720 ) ], vec![ Value::Number( 1000 ), Value::AtMost( 300 ) ] ),
+      ( vec![ Value::Range( 600, 1000 ), Value::Number( 240 ) ], vec![ Value::Range( 250, 350 ), Value::AtLeast( 900 ) ] ),
+      ( vec![ Value::Range( 1000, 600 ), Value::AtLeast( 840 ) ], vec![ Value::Range( 700, 900 ), Value::AtMost( 100 ) ] ),
+      ( vec![ Value::Index( 0 ), Value::Index( 3 ), Value::Index( 100 ) ], vec![ Value::Index( 1 ), Value::Index( 7 ) ] ),
+      ( vec![ Value::Number( 500 ) ], vec![ Value::Any ] ),
+      ( vec![], vec![ Value::Any ] ),
+      ( vec![ Value::Any ], vec![] ),
+    ];
+    for ( allowed, preferred ) in cases
+    {
+      assert_eq!( attempt_rle( &ranges, &allowed, &preferred ), attempt( &available, &allowed, &preferred ) );
+    }
+
+    assert_eq!( attempt_rle( &[ ( 0, i32::MAX, 1 ) ], &[ Value::Any ], &[ Value::AtMost( -1 ) ] ), vec![ 0 ] );
     assert_eq!
     (
-      attempt
-      (
-        &vec![ 240, 360, 720 ],
-        &vec!
-        [
-          Value::Number( 240 ),
-          Value::Number( 360 ),
-          Value::Number( 720 ),
-          Value::Number( 1080 )
-        ],
-        &vec![ Value::Number( 240 ), Value::Number( 360 ) ]
-      ),
-      vec![ 240, 360 ]
+      attempt_rle( &[ ( 0, i32::MAX, 2 ) ], &[ Value::AtLeast( 1001 ) ], &[ Value::Number( 5 ), Value::Index( 2 ) ] ),
+      vec![ 1002, 1006 ]
+    );
+    assert_eq!( attempt_rle( &[ ( 10, 0, 1 ), ( 20, 30, 0 ) ], &[ Value::Any ], &[ Value::Range( 0, 22 ) ] ), vec![ 20, 21, 22 ] );
+    assert_eq!( attempt_rle( &[], &[ Value::Any ], &[ Value::Any ] ), vec![] );
+  }
+
+
+  #[ test ]
+  fn test_min_cover()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let preferred = [ Value::Number( 300 ), Value::Number( 400 ), Value::Number( 700 ), Value::Number( 2000 ) ];
+    assert_eq!( min_cover( &available, &[ Value::Any ], &preferred, 60 ), vec![ 360, 720 ] );
+    assert_eq!( min_cover( &available, &[ Value::Any ], &preferred, 0 ), vec![] );
+    assert_eq!( min_cover( &available, &[ Value::Any ], &preferred, 1000 ), vec![ 1080 ] );
+    assert_eq!( min_cover( &available, &[ Value::AtMost( 480 ) ], &preferred, 300 ), vec![ 480 ] );
+    assert_eq!( min_cover( &available, &[ Value::Any ], &[ Value::Number( 480 ), Value::Any, Value::Range( 0, 100 ) ], 0 ), vec![ 480 ] );
+    assert_eq!( min_cover( &available, &[ Value::Number( 100 ) ], &preferred, 1000 ), vec![] );
+
+    let mut next = xorshift( 0x9e37_79b9_7f4a_7c15 );
+    for _ in 0..500
+    {
+      let available : Vec< i32 > = ( 0..next( 8 ) ).map( | _ | next( 40 ) as i32 ).collect();
+      let preferred : Vec< Value > = ( 0..next( 7 ) ).map( | _ | Value::Number( next( 40 ) as i32 ) ).collect();
+      let tol = next( 8 );
+      let ladder = reduce_by_allowed( &sorted( &available ), &[ Value::Any ] );
+      let covered = | set : &[ i32 ] | -> Vec< i32 >
+      {
+        preferred.iter()
+        .filter_map( | value | match value
+        {
+          Value::Number( target ) if set.iter().any( | num | num.distance( *target ) <= tol ) => Some( *target ),
+          _ => None,
+        })
+        .collect()
+      };
+      let best = ( 0..1u32 << ladder.len() )
+      .map( | mask | ( 0..ladder.len() ).filter( | bit | mask >> bit & 1 == 1 ).map( | bit | ladder[ bit ] ).collect::< Vec< _ > >() )
+      .filter( | set | covered( set ) == covered( &ladder ) )
+      .map( | set | set.len() )
+      .min()
+      .unwrap_or_default();
+
+      let cover = min_cover( &available, &[ Value::Any ], &preferred, tol );
+      let harmonic : f64 = ( 1..=preferred.len() ).map( | count | 1.0 / count as f64 ).sum();
+      assert_eq!( covered( &cover ), covered( &ladder ), "available: {available:?}, preferred: {preferred:?}, tol: {tol}" );
+      assert!( cover.len() >= best );
+      assert!( cover.len() as f64 <= best as f64 * harmonic.max( 1.0 ), "available: {available:?}, preferred: {preferred:?}, tol: {tol}" );
+    }
+  }
+
+
+  #[ test ]
+  fn test_attempt_sum_capped()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let preferred = [ Value::Number( 720 ), Value::Number( 240 ), Value::Range( 300, 500 ) ];
+    assert_eq!( attempt_sum_capped( &available, &[ Value::Any ], &preferred, 10000 ), vec![ 720, 240, 360, 480 ] );
+    assert_eq!( attempt_sum_capped( &available, &[ Value::Any ], &preferred, 1800 ), vec![ 720, 240, 360, 480 ] );
+    assert_eq!( attempt_sum_capped( &available, &[ Value::Any ], &preferred, 1799 ), vec![ 720, 240, 360 ] );
+    assert_eq!( attempt_sum_capped( &available, &[ Value::Any ], &preferred, 960 ), vec![ 720, 240 ] );
+    assert_eq!( attempt_sum_capped( &available, &[ Value::Any ], &preferred, 959 ), vec![ 720 ] );
+    assert_eq!( attempt_sum_capped( &available, &[ Value::Any ], &preferred, 719 ), vec![] );
+    assert_eq!( attempt_sum_capped( &available, &[ Value::AtMost( 480 ) ], &preferred, 720 ), vec![ 480, 240 ] );
+    assert_eq!( attempt_sum_capped( &available, &[ Value::Any ], &[], -1 ), vec![] );
+  }
+
+
+  #[ test ]
+  fn test_attempt_tiered()
+  {
+    let available = [ 240, 360, 480, 720, 1080, 2160 ];
+    let tiers = [ ( 0, 480 ), ( 1080, 720 ), ( 2160, 2160 ) ];
+    let preferred = [ Value::Number( 480 ), Value::Number( 1080 ), Value::Number( 240 ), Value::Number( 720 ), Value::Number( 360 ) ];
+    assert_eq!( attempt_tiered( &available, &tiers, &[ Value::Any ], &preferred, 1 ), vec![ 480, 1080 ] );
+    assert_eq!( attempt_tiered( &available, &tiers, &[ Value::Any ], &preferred, 2 ), vec![ 480, 1080, 240, 720 ] );
+    assert_eq!( attempt_tiered( &available, &tiers, &[ Value::Any ], &preferred, 5 ), vec![ 480, 1080, 240, 720, 360 ] );
+    assert_eq!( attempt_tiered( &available, &tiers, &[ Value::Any ], &preferred, 0 ), vec![] );
+    assert_eq!( attempt_tiered( &available, &[ ( 0, 480 ) ], &[ Value::Any ], &preferred, 1 ), vec![ 480, 1080, 720 ] );
+    assert_eq!( attempt_tiered( &available, &[ ( 0, 720 ), ( 480, 2160 ) ], &[ Value::Any ], &[ Value::Any ], 2 ), vec![ 240, 360, 1080, 2160 ] );
+    assert_eq!( attempt_tiered( &available, &[], &[ Value::AtMost( 360 ) ], &[ Value::Any ], 0 ), vec![ 240, 360 ] );
+  }
+
+
+  #[ cfg( feature = "std" ) ]
+  #[ test ]
+  fn test_selection_entropy()
+  {
+    assert_eq!( selection_entropy::< i32 >( &[] ), 0.0 );
+    assert_eq!( selection_entropy::< i32 >( &[ vec![], vec![] ] ), 0.0 );
+    assert_eq!( selection_entropy( &[ vec![ 720 ], vec![ 720 ], vec![ 720 ] ] ), 0.0 );
+    assert_eq!( selection_entropy( &[ vec![ 240 ], vec![ 720 ] ] ), 1.0 );
+    assert_eq!( selection_entropy( &[ vec![ 240, 360 ], vec![ 480, 720 ], vec![ 1080, 1440, 2160, 4320 ] ] ), 3.0 );
+    assert_eq!( selection_entropy( &[ vec![ 240, 240 ], vec![ 360 ], vec![ 720 ] ] ), 1.5 );
+
+    let available = [ 240, 360, 720 ];
+    let results : Vec< _ > = [ 200, 300, 700, 1000 ].iter()
+    .map( | num | attempt( &available, &[ Value::Any ], &[ Value::Number( *num ) ] ) )
+    .collect();
+    assert_eq!( selection_entropy( &results ), 1.5 );
+  }
+
+
+  #[ test ]
+  fn test_reduce_by_preferred_tiebreak()
+  {
+    let original = [ 240, 360, 720, 1080 ];
+    let preferred = [ Value::Number( 300 ), Value::Number( 540 ), Value::Number( 1000 ) ];
+    assert_eq!( reduce_by_preferred_tiebreak( &original, &preferred, | a, b | a.cmp( &b ) ), vec![ 240, 360, 1080 ] );
+    assert_eq!( reduce_by_preferred_tiebreak( &original, &preferred, | a, b | b.cmp( &a ) ), vec![ 360, 720, 1080 ] );
+    assert_eq!
+    (
+      reduce_by_preferred_tiebreak( &original, &preferred, | a, b | a.cmp( &b ) ),
+      reduce_by_preferred_with( &original, &preferred, TieBreak::PreferNearest )
     );
+    assert_eq!( reduce_by_preferred_tiebreak( &original, &preferred, | _, _ | core::cmp::Ordering::Equal ), vec![ 240, 360, 1080 ] );
+
+    let supported = [ 720 ];
+    let by_support = | a : i32, b : i32 | supported.contains( &b ).cmp( &supported.contains( &a ) );
+    assert_eq!( reduce_by_preferred_tiebreak( &original, &[ Value::Number( 540 ), Value::Number( 900 ) ], by_support ), vec![ 720 ] );
+    assert_eq!( reduce_by_preferred_tiebreak( &original, &[ Value::Number( 100 ), Value::Range( 480, 600 ) ], by_support ), vec![ 240, 720 ] );
+    assert_eq!( reduce_by_preferred_tiebreak( &original, &[ Value::Any ], by_support ), original.to_vec() );
+    assert_eq!( reduce_by_preferred_tiebreak( &[], &[ Value::Number( 540 ) ], by_support ), vec![] );
+  }
+
+
+  #[ test ]
+  fn test_reduce_by_preferred_transform()
+  {
+    let original = [ 100, 1000, 10000 ];
+    let preferred = [ Value::Number( 400 ), Value::Number( 2000 ), Value::Number( 20 ), Value::Number( 50000 ) ];
+    assert_eq!( reduce_by_preferred_transform( &original, &preferred, f64::from ), vec![ 100, 1000, 10000 ] );
     assert_eq!
     (
-      attempt
-      (
-        &vec![ 240, 720 ],
-        &vec!
-        [
-          Value::Number( 240 ),
-          Value::Number( 360 ),
-          Value::Number( 720 ),
-          Value::Number( 1080 )
-        ],
-        &vec![ Value::Number( 240 ), Value::Number( 360 ) ]
-      ),
-      vec![ 240, 720 ]
+      reduce_by_preferred_transform( &original, &preferred, f64::from ),
+      reduce_by_preferred_with( &original, &preferred, TieBreak::PreferNearest )
     );
+    assert_eq!( reduce_by_preferred_transform( &original, &preferred, | num | f64::from( num ).log10() ), vec![ 1000, 100, 10000 ] );
+    assert_eq!( reduce_by_preferred_transform( &original, &[ Value::Range( 200, 300 ) ], | num | f64::from( num ).log10() ), vec![ 100 ] );
+    assert_eq!( reduce_by_preferred_transform( &original, &[ Value::Range( 300, 400 ) ], | num | f64::from( num ).log10() ), vec![ 1000 ] );
+    assert_eq!( reduce_by_preferred_transform( &original, &[ Value::Number( 1000 ), Value::Range( 1, 100 ) ], f64::from ), vec![ 1000, 100 ] );
+    assert_eq!( reduce_by_preferred_transform( &original, &[ Value::Number( 550 ) ], f64::from ), vec![ 100 ] );
+    assert_eq!( reduce_by_preferred_transform( &original, &[ Value::Any ], f64::from ), original.to_vec() );
+    assert_eq!( reduce_by_preferred_transform( &[], &[ Value::Number( 550 ) ], f64::from ), vec![] );
+  }
+
+
+  #[ test ]
+  fn test_attempt_outcome()
+  {
+    let available = [ 720, 240, 360 ];
     assert_eq!
     (
-      attempt
-      (
-        &vec![ 240, 720 ],
-        &vec![ Value::Number( 240 ), Value::Number( 360 ), Value::Number( 1080 ) ],
-        &vec![ Value::Number( 240 ), Value::Number( 360 ) ]
-      ),
-      vec![ 240 ]
+      attempt_outcome( &available, &[ Value::AtLeast( 300 ) ], &[ Value::Number( 100 ), Value::Number( 720 ) ] ),
+      Outcome::Selected( vec![ 360, 720 ] )
     );
+    assert_eq!( attempt_outcome( &available, &[ Value::Any ], &[ Value::Any ] ), Outcome::Selected( vec![ 240, 360, 720 ] ) );
+    assert_eq!( attempt_outcome( &available, &[ Value::Index( 2 ) ], &[] ), Outcome::Empty { had_candidates : true } );
+    assert_eq!( attempt_outcome( &available, &[ Value::Index( 3 ) ], &[ Value::Any ] ), Outcome::Empty { had_candidates : false } );
+    assert_eq!( attempt_outcome( &available, &[], &[ Value::Any ] ), Outcome::Empty { had_candidates : false } );
+    assert_eq!( attempt_outcome( &[], &[ Value::Any ], &[ Value::Any ] ), Outcome::Empty { had_candidates : false } );
+  }
+
+
+  #[ test ]
+  fn test_attempt_spread()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let preferred = [ Value::Number( 720 ), Value::Range( 300, 500 ), Value::Number( 240 ), Value::Number( 1080 ) ];
+    assert_eq!( attempt_spread( &available, &[ Value::Any ], &preferred, 0 ), vec![ 720, 360, 480, 240, 1080 ] );
+    assert_eq!( attempt_spread( &available, &[ Value::Any ], &preferred, 120 ), vec![ 720, 360, 480, 240, 1080 ] );
+    assert_eq!( attempt_spread( &available, &[ Value::Any ], &preferred, 121 ), vec![ 720, 360, 1080 ] );
+    assert_eq!( attempt_spread( &available, &[ Value::Any ], &preferred, 360 ), vec![ 720, 360, 1080 ] );
+    assert_eq!( attempt_spread( &available, &[ Value::Any ], &preferred, 361 ), vec![ 720, 240 ] );
+    assert_eq!( attempt_spread( &available, &[ Value::AtMost( 480 ) ], &[ Value::Any ], 200 ), vec![ 240, 480 ] );
+    assert_eq!( attempt_spread( &available, &[ Value::Any ], &[], 200 ), vec![] );
+  }
+
+
+  #[ test ]
+  fn test_coverage_fraction()
+  {
+    let available = [ 720, 240, 360, 360, 480 ];
+    assert_eq!( coverage_fraction( &available, &[ Value::Any ], &[ Value::Number( 360 ), Value::Number( 300 ) ] ), 0.25 );
+    assert_eq!( coverage_fraction( &available, &[ Value::Any ], &[ Value::Range( 300, 500 ) ] ), 0.5 );
+    assert_eq!( coverage_fraction( &available, &[ Value::Any ], &[ Value::Any ] ), 1.0 );
+    assert_eq!( coverage_fraction( &available, &[ Value::AtMost( 360 ) ], &[ Value::Number( 1080 ) ] ), 0.5 );
+    assert_eq!( coverage_fraction( &available, &[ Value::Any ], &[] ), 0.0 );
+    assert_eq!( coverage_fraction( &available, &[ Value::Number( 100 ) ], &[ Value::Any ] ), 0.0 );
+    assert_eq!( coverage_fraction::< i32 >( &[], &[ Value::Any ], &[ Value::Any ] ), 0.0 );
+  }
+
+
+  #[ test ]
+  fn test_intersect_sorted_simd()
+  {
+    assert_eq!( intersect_sorted_simd( &[ 240, 360, 480, 720 ], &[ 100, 360, 720, 1080 ] ), vec![ 360, 720 ] );
+    assert_eq!( intersect_sorted_simd( &[ 240, 240, 360, 360 ], &[ 240, 360, 360 ] ), vec![ 240, 360 ] );
+    assert_eq!( intersect_sorted_simd( &[], &[ 240 ] ), vec![] );
+    assert_eq!( intersect_sorted_simd( &[ 240 ], &[] ), vec![] );
+
+    let mut next = xorshift( 0x1234_5678_9abc_def1 );
+    for _ in 0..500
+    {
+      let mut original : Vec< i32 > = ( 0..next( 40 ) ).map( | _ | next( 100 ) as i32 - 50 ).collect();
+      let mut allowed : Vec< i32 > = ( 0..next( 40 ) ).map( | _ | next( 100 ) as i32 - 50 ).collect();
+      original.sort();
+      allowed.sort();
+      let mut expected : Vec< i32 > = original.iter().copied().filter( | num | allowed.contains( num ) ).collect();
+      expected.dedup();
+      let numbers : Vec< _ > = allowed.iter().rev().map( | num | Value::Number( *num ) ).collect();
+      assert_eq!( intersect_sorted_simd( &original, &allowed ), expected, "original: {original:?}, allowed: {allowed:?}" );
+      assert_eq!( reduce_by_allowed( &original, &numbers ), expected, "original: {original:?}, allowed: {allowed:?}" );
+    }
+  }
+
+  #[ test ]
+  fn test_best_served()
+  {
+    let available = [ 240, 360, 720, 1080 ];
+    let preferred = [ Value::Any, Value::Number( 300 ), Value::Number( 2000 ), Value::Number( 700 ), Value::Number( 740 ) ];
+    assert_eq!( best_served( &available, &[ Value::Any ], &preferred ), Some( ( 3, 720, 20 ) ) );
+    assert_eq!( best_served( &available, &[ Value::AtMost( 360 ) ], &preferred ), Some( ( 1, 360, 60 ) ) );
+    assert_eq!( best_served( &available, &[ Value::Any ], &[ Value::Number( 100 ), Value::Number( 1200 ) ] ), Some( ( 1, 1080, 120 ) ) );
+    assert_eq!( best_served( &available, &[ Value::Any ], &[ Value::Number( 120 ), Value::Number( 1200 ) ] ), Some( ( 0, 240, 120 ) ) );
+    assert_eq!( best_served( &available, &[ Value::Any ], &[ Value::Any, Value::Range( 0, 100 ) ] ), None );
+    assert_eq!( best_served( &available, &[ Value::Number( 480 ) ], &preferred ), None );
+  }
+
+
+  #[ test ]
+  fn test_attempt_over_snapshots()
+  {
+    let snapshots =
+    [
+      ( 300, vec![ 720, 240, 1080 ] ),
+      ( 100, vec![ 240, 360 ] ),
+      ( 200, vec![] ),
+    ];
     assert_eq!
     (
-      attempt
-      (
-        &vec![ 720 ],
-        &vec![ Value::Number( 240 ), Value::Number( 360 ), Value::Number( 1080 ) ],
-        &vec![ Value::Number( 240 ), Value::Number( 360 ) ]
-      ),
-      vec![]
+      attempt_over_snapshots( &snapshots, &[ Value::AtMost( 720 ) ], &[ Value::Number( 360 ), Value::Number( 1080 ) ] ),
+      vec![ ( 300, vec![ 720 ] ), ( 100, vec![ 360 ] ), ( 200, vec![] ) ]
     );
     assert_eq!
     (
-      attempt
-      (
-        &vec![ 240, 360 ],
-        &vec![ Value::Number( 240 ), Value::Number( 360 ) ],
-        &vec![ Value::Number( 720 ), Value::Number( 1080 ) ]
-      ),
-      vec![ 360 ]
+      attempt_over_snapshots( &snapshots, &[ Value::Any ], &[ Value::Any ] ),
+      vec![ ( 300, vec![ 240, 720, 1080 ] ), ( 100, vec![ 240, 360 ] ), ( 200, vec![] ) ]
     );
+    assert_eq!( attempt_over_snapshots::< i32 >( &[], &[ Value::Any ], &[ Value::Any ] ), vec![] );
   }
 
+
   #[ test ]
-  fn test_attempt_with_any() 
+  fn test_attempt_contract()
   {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let contract = [ 240, 480, 720 ];
+    let preferred = [ Value::Number( 720 ), Value::Number( 240 ) ];
+    assert_eq!( attempt_contract( &available, &[ Value::Any ], &preferred, &contract ), Ok( vec![ 720, 240 ] ) );
     assert_eq!
     (
-      attempt
-      (
-        &vec![ 240, 360, 720 ],
-        &vec![ Value::Number( 360 ), Value::Any ],
-        &vec![ Value::Number( 360 ), Value::Number( 720 ) ]
-      ),
-      vec![ 360, 720 ]
+      attempt_contract( &available, &[ Value::Any ], &[ Value::Number( 1080 ), Value::Number( 720 ), Value::Number( 300 ) ], &contract ),
+      Err( ContractViolation { forbidden : vec![ 1080, 360 ] } )
     );
     assert_eq!
     (
-      attempt
-      (
-        &vec![ 240, 360, 720 ],
-        &vec![ Value::Number( 240 ), Value::Number( 360 ), Value::Number( 720 ) ],
-        &vec![ Value::Any, Value::Number( 720 ) ]
-      ),
-      vec![ 240, 360, 720 ]
+      attempt_contract( &available, &[ Value::Any ], &[ Value::Any ], &[] ),
+      Err( ContractViolation { forbidden : available.to_vec() } )
     );
+    assert_eq!( attempt_contract( &available, &[ Value::Number( 100 ) ], &[ Value::Any ], &[] ), Ok( vec![] ) );
     assert_eq!
     (
-      attempt
-      (
-        &vec![ 240, 360, 720 ],
-        &vec![ Value::Number( 360 ), Value::Number( 1080 ) ],
-        &vec![ Value::Any, Value::Number( 720 ) ]
-      ),
-      vec![ 360 ]
+      alloc::format!( "{}", ContractViolation { forbidden : vec![ 1080, 360 ] } ),
+      "selected values [1080, 360] are outside of the contract"
     );
+  }
+
+
+  #[ test ]
+  fn test_minimax_rung()
+  {
+    let available = [ 1080, 240, 360, 720 ];
+    let preferred = [ Value::Number( 300 ), Value::Number( 1000 ), Value::Number( 700 ), Value::Any ];
+    assert_eq!( minimax_rung( &available, &[ Value::Any ], &preferred ), Some( 720 ) );
+    assert_eq!( minimax_rung( &available, &[ Value::AtMost( 360 ) ], &preferred ), Some( 360 ) );
+    assert_eq!( minimax_rung( &available, &[ Value::Any ], &[ Value::Number( 2000 ) ] ), Some( 1080 ) );
+    assert_eq!( minimax_rung( &available, &[ Value::Any ], &[ Value::Number( 300 ) ] ), Some( 240 ) );
+    assert_eq!( minimax_rung( &available, &[ Value::Any ], &[ Value::Number( 0 ), Value::Number( 600 ) ] ), Some( 240 ) );
+    assert_eq!( minimax_rung( &available, &[ Value::Any ], &[ Value::Any, Value::Range( 0, 100 ) ] ), None );
+    assert_eq!( minimax_rung( &available, &[ Value::Number( 480 ) ], &preferred ), None );
+  }
+
+
+  #[ test ]
+  fn test_attempt_brackets()
+  {
+    let available = [ 720, 240, 360, 1080 ];
+    let preferred = [ Value::Number( 100 ), Value::Any, Value::Number( 720 ), Value::Range( 0, 100 ), Value::Number( 2000 ) ];
     assert_eq!
     (
-      attempt
-      (
-        &vec![ 240, 360, 720 ],
-        &vec![ Value::Number( 1080 ) ],
-        &vec![ Value::Any, Value::Number( 720 ) ]
-      ),
-      vec![]
+      attempt_brackets( &available, &[ Value::Any ], &preferred ),
+      vec![ ( None, Some( 240 ) ), ( Some( 720 ), Some( 720 ) ), ( Some( 1080 ), None ) ]
     );
     assert_eq!
     (
-      attempt( &vec![ 240, 360, 720 ], &vec![ Value::Any ], &vec![ Value::Any ] ),
-      vec![ 240, 360, 720 ]
+      attempt_brackets( &available, &[ Value::Number( 240 ), Value::Number( 1080 ) ], &preferred ),
+      vec![ ( None, Some( 240 ) ), ( Some( 240 ), Some( 1080 ) ), ( Some( 1080 ), None ) ]
+    );
+    assert_eq!( attempt_brackets( &available, &[ Value::Number( 480 ) ], &preferred ), vec![ ( None, None ); 3 ] );
+    assert_eq!( attempt_brackets( &available, &[ Value::Any ], &[ Value::Any ] ), vec![] );
+  }
+
+
+  #[ test ]
+  fn test_reduce_by_preferred_seeded()
+  {
+    let original = [ 240, 360, 720, 1080 ];
+    let preferred = [ Value::Number( 250 ), Value::Number( 700 ), Value::Number( 2000 ), Value::Range( 0, 100 ) ];
+    for seed in 0..16
+    {
+      assert_eq!( reduce_by_preferred_seeded( &original, &preferred, seed ), vec![ 240, 720, 1080 ] );
+    }
+    assert_eq!( reduce_by_preferred_seeded( &original, &[ Value::Any ], 0 ), original.to_vec() );
+    assert_eq!( reduce_by_preferred_seeded( &[], &[ Value::Number( 540 ) ], 0 ), vec![] );
+
+    let tied = [ Value::Number( 540 ) ];
+    let picks : Vec< _ > = ( 0..64 ).map( | seed | reduce_by_preferred_seeded( &original, &tied, seed ) ).collect();
+    assert!( picks.iter().all( | pick | *pick == vec![ 360 ] || *pick == vec![ 720 ] ) );
+    assert!( picks.contains( &vec![ 360 ] ) );
+    assert!( picks.contains( &vec![ 720 ] ) );
+    for ( seed, pick ) in picks.iter().enumerate()
+    {
+      assert_eq!( reduce_by_preferred_seeded( &original, &tied, seed as u64 ), *pick );
+    }
+    let first : Vec< i32 > = picks[ ..8 ].iter().map( | pick | pick[ 0 ] ).collect();
+    assert_eq!( first, vec![ 360, 360, 360, 720, 360, 360, 360, 360 ] );
+    let wide : Vec< u64 > = ( 0..8 ).map( | seed | reduce_by_preferred_seeded( &[ 360u64, 720 ], &[ ValueT::Number( 540 ) ], seed )[ 0 ] ).collect();
+    assert_eq!( wide, first.iter().map( | num | *num as u64 ).collect::< Vec< _ > >() );
+
+    let ladder : Vec< i32 > = ( 0..200 ).map( | num | num * 10 ).collect();
+    let lower = ( 0..199 )
+    .filter( | num | reduce_by_preferred_seeded( &ladder, &[ Value::Number( num * 10 + 5 ) ], 42 ) == vec![ num * 10 ] )
+    .count();
+    assert!( 50 < lower && lower < 150, "{lower} of 199 ties picked the lower value" );
+  }
+
+
+  #[ test ]
+  fn test_attempt_with_overrides()
+  {
+    let available = [ 1080, 240, 360, 720 ];
+    let preferred = [ Value::Number( 1080 ), Value::Number( 300 ), Value::Number( 480 ), Value::Range( 200, 300 ) ];
+    let overrides = [ ( 1080, 720 ), ( 480, 100 ), ( 300, 1080 ), ( 300, 240 ) ];
+    assert_eq!( attempt_with_overrides( &available, &[ Value::Any ], &preferred, &overrides ), vec![ 720, 1080, 240 ] );
+    assert_eq!( attempt_with_overrides( &available, &[ Value::Any ], &preferred, &[] ), attempt( &available, &[ Value::Any ], &preferred ) );
+    assert_eq!
+    (
+      attempt_with_overrides( &available, &[ Value::AtMost( 720 ) ], &preferred, &overrides ),
+      vec![ 720, 360, 240 ]
     );
+    assert_eq!( attempt_with_overrides( &available, &[ Value::Any ], &[ Value::Any ], &overrides ), vec![ 240, 360, 720, 1080 ] );
+    assert_eq!( attempt_with_overrides( &available, &[ Value::Number( 100 ) ], &preferred, &overrides ), vec![] );
+  }
+
+
+  #[ test ]
+  fn test_perturbation_stability()
+  {
+    let available = [ 240, 360, 480, 720, 1080 ];
+    let preferred = [ Value::Number( 360 ), Value::Number( 1080 ) ];
+    assert_eq!( perturbation_stability( &available, &[ Value::Any ], &preferred, 0, 50, 1 ), 1.0 );
+    assert_eq!( perturbation_stability( &available, &[ Value::Any ], &preferred, 50, 0, 1 ), 1.0 );
+    assert_eq!( perturbation_stability( &available, &[ Value::Any ], &[ Value::Any ], 50, 50, 1 ), 1.0 );
+    assert_eq!( perturbation_stability( &available, &[ Value::Any ], &[ Value::Range( 300, 600 ) ], 50, 50, 1 ), 1.0 );
+    assert_eq!( perturbation_stability( &available, &[ Value::Number( 720 ), Value::Number( 360 ) ], &[ Value::Any ], 50, 50, 1 ), 1.0 );
+    assert_eq!( perturbation_stability( &available, &[ Value::Any ], &[ Value::AtLeast( 600 ) ], -50, 50, 1 ), 1.0 );
+
+    let stability = perturbation_stability( &available, &[ Value::Any ], &preferred, 10, 200, 1 );
+    assert!( 0.0 < stability && stability < 1.0, "stability: {stability}" );
+    assert_eq!( perturbation_stability( &available, &[ Value::Any ], &preferred, 10, 200, 1 ), stability );
+    assert!( perturbation_stability( &available, &[ Value::Any ], &preferred, 100, 200, 1 ) < stability );
+    assert!( ( 0.0..=1.0 ).contains( &perturbation_stability( &[ i32::MIN, i32::MAX ], &[ Value::Any ], &[ Value::Any ], 10, 10, 1 ) ) );
+  }
+
+
+  #[ test ]
+  fn test_attempt_curve()
+  {
+    let available = [ 1080, 240, 360, 720 ];
+    let targets = [ 250, 700, 250, 1000, 1100, 350 ];
+    assert_eq!( attempt_curve( &available, &[ Value::Any ], &targets, 5 ), vec![ 240, 720, 240, 1080, 1080, 360 ] );
+    assert_eq!( attempt_curve( &available, &[ Value::Any ], &targets, 100 ), vec![ 240, 720, 240, 1080, 1080, 360 ] );
+    assert_eq!( attempt_curve( &available, &[ Value::Any ], &targets, 0 ), vec![ 360; 6 ] );
+    assert_eq!( attempt_curve( &available, &[ Value::Any ], &targets, 1 ), vec![ 240, 240, 240, 1080, 1080, 1080 ] );
+    assert_eq!( attempt_curve( &available, &[ Value::AtMost( 720 ) ], &targets, 1 ), vec![ 240, 240, 240, 720, 720, 720 ] );
+    assert_eq!( attempt_curve( &available, &[ Value::Any ], &[ 720, 720, 720 ], 2 ), vec![ 720; 3 ] );
+    assert_eq!( attempt_curve( &[ 240, 240, 720 ], &[ Value::Any ], &[ 240, 720 ], 1 ), vec![ 240, 720 ] );
+    assert_eq!( attempt_curve( &available, &[ Value::Number( 100 ) ], &targets, 1 ), vec![] );
+    assert_eq!( attempt_curve( &available, &[ Value::Any ], &[], 1 ), vec![] );
+
+    let targets : Vec< i32 > = ( 0..2000 ).map( | t | [ 250, 700, 1000, 350, 700 ][ t % 5 ] ).collect();
+    let nearest : Vec< i32 > = ( 0..2000 ).map( | t | [ 240, 720, 1080, 360, 720 ][ t % 5 ] ).collect();
+    assert_eq!( attempt_curve( &available, &[ Value::Any ], &targets, usize::MAX ), nearest );
+    assert_eq!( attempt_curve( &available, &[ Value::Any ], &targets, 0 ), vec![ 720; 2000 ] );
+
+    let mut next = xorshift( 0x5851_f42d_4c95_7f2d );
+    for _ in 0..300
+    {
+      let available : Vec< i32 > = ( 0..1 + next( 4 ) ).map( | _ | next( 30 ) as i32 ).collect();
+      let targets : Vec< i32 > = ( 0..1 + next( 6 ) ).map( | _ | next( 30 ) as i32 ).collect();
+      let max_switches = next( 4 ) as usize;
+      let ladder = sorted( &available ).into_owned();
+      let cost = | sequence : &[ i32 ] | -> u64 { sequence.iter().zip( &targets ).map( | ( num, target ) | num.distance( *target ) ).sum() };
+      let switches = | sequence : &[ i32 ] | sequence.windows( 2 ).filter( | pair | pair[ 0 ] != pair[ 1 ] ).count();
+
+      let mut best = u64::MAX;
+      let mut sequence = vec![ 0; targets.len() ];
+      for code in 0..ladder.len().pow( targets.len() as u32 )
+      {
+        let mut rest = code;
+        for num in sequence.iter_mut()
+        {
+          *num = ladder[ rest % ladder.len() ];
+          rest /= ladder.len();
+        }
+        if switches( &sequence ) <= max_switches
+        {
+          best = best.min( cost( &sequence ) );
+        }
+      }
+
+      let curve = attempt_curve( &available, &[ Value::Any ], &targets, max_switches );
+      assert_eq!( curve.len(), targets.len() );
+      assert!( curve.iter().all( | num | available.contains( num ) ) );
+      assert!( switches( &curve ) <= max_switches, "available: {available:?}, targets: {targets:?}, curve: {curve:?}" );
+      assert_eq!( cost( &curve ), best, "available: {available:?}, targets: {targets:?}, curve: {curve:?}" );
+    }
   }
 }
@@ -0,0 +1,50 @@
+#![ cfg( feature = "serde" ) ]
+
+use attempt::*;
+
+#[ test ]
+fn value_round_trip()
+{
+  let values = vec!
+  [
+    Value::Number( 360 ),
+    Value::Range( 480, 720 ),
+    Value::AtLeast( 1080 ),
+    Value::AtMost( 2160 ),
+    Value::Index( 2 ),
+    Value::Any,
+  ];
+  let json = serde_json::to_string( &values ).unwrap();
+  assert_eq!( json, r#"[360,[480,720],{"at_least":1080},{"at_most":2160},{"index":2},"any"]"# );
+  assert_eq!( serde_json::from_str::< Vec< Value > >( &json ).unwrap(), values );
+}
+
+#[ test ]
+fn value_deserialize()
+{
+  assert_eq!
+  (
+    serde_json::from_str::< Vec< ValueT< u64 > > >( r#"[ "any", 5000000000 ]"# ).unwrap(),
+    vec![ ValueT::Any, ValueT::Number( 5_000_000_000 ) ]
+  );
+  assert!( serde_json::from_str::< Value >( r#""all""# ).is_err() );
+  assert!( serde_json::from_str::< Value >( "3.5" ).is_err() );
+}
+
+#[ test ]
+fn audit_log_round_trip()
+{
+  let mut log = AuditLog::new();
+  attempt_audited( &[ 240, 720 ], &[ Value::AtLeast( 300 ) ], &[ Value::Number( 360 ), Value::Number( 720 ) ], &mut log );
+  let json = serde_json::to_string( &log ).unwrap();
+  assert_eq!
+  (
+    json,
+    concat!
+    (
+      r#"{"events":[{"allowed":{"value":240,"allowed":false}},{"allowed":{"value":720,"allowed":true}},"#,
+      r#"{"selected":{"preference":0,"value":720,"kind":"clamped_low"}},{"collapsed":{"preference":1,"value":720}}]}"#
+    )
+  );
+  assert_eq!( serde_json::from_str::< AuditLog< i32 > >( &json ).unwrap(), log );
+}